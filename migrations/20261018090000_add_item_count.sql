@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS item_count (system TEXT NOT NULL, type TEXT NOT NULL, count INTEGER NOT NULL, PRIMARY KEY (system, type)) STRICT;
+
+INSERT INTO item_count (system, type, count) SELECT IFNULL(system, ''), IFNULL(type, ''), COUNT(1) FROM item GROUP BY 1, 2;
+
+CREATE TRIGGER IF NOT EXISTS item_count_insert AFTER INSERT ON item BEGIN
+    INSERT INTO item_count (system, type, count) VALUES (IFNULL(NEW.system, ''), IFNULL(NEW.type, ''), 1) ON CONFLICT (system, type) DO UPDATE SET count = count + 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS item_count_delete AFTER DELETE ON item BEGIN
+    UPDATE item_count SET count = count - 1 WHERE system = IFNULL(OLD.system, '') AND type = IFNULL(OLD.type, '');
+END;
+
+CREATE TRIGGER IF NOT EXISTS item_count_update AFTER UPDATE OF system, type ON item BEGIN
+    UPDATE item_count SET count = count - 1 WHERE system = IFNULL(OLD.system, '') AND type = IFNULL(OLD.type, '');
+    INSERT INTO item_count (system, type, count) VALUES (IFNULL(NEW.system, ''), IFNULL(NEW.type, ''), 1) ON CONFLICT (system, type) DO UPDATE SET count = count + 1;
+END;
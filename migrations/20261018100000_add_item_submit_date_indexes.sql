@@ -0,0 +1,2 @@
+CREATE INDEX IF NOT EXISTS idx_item_submit_date_system ON item (submit_date, system);
+CREATE INDEX IF NOT EXISTS idx_item_submit_date_type ON item (submit_date, type);
@@ -0,0 +1 @@
+CREATE TABLE IF NOT EXISTS saved_search (name TEXT PRIMARY KEY, owner TEXT, description TEXT, filter TEXT NOT NULL, created_date TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP, updated_date TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP) STRICT;
@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS item_tag (item_id INTEGER NOT NULL REFERENCES item (id) ON DELETE CASCADE, tag TEXT NOT NULL, created_date TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP, PRIMARY KEY (item_id, tag)) STRICT;
+CREATE TABLE IF NOT EXISTS item_note (id INTEGER PRIMARY KEY AUTOINCREMENT, item_id INTEGER NOT NULL REFERENCES item (id) ON DELETE CASCADE, author TEXT, text TEXT NOT NULL, created_date TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP) STRICT;
+
+CREATE INDEX IF NOT EXISTS idx_item_note_item_id ON item_note (item_id);
+CREATE INDEX IF NOT EXISTS idx_item_tag_tag ON item_tag (tag);
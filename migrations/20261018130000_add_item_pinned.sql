@@ -0,0 +1,7 @@
+ALTER TABLE item ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_item_pinned ON item (id) WHERE pinned = 1;
+
+CREATE TRIGGER IF NOT EXISTS item_pinned_delete BEFORE DELETE ON item WHEN OLD.pinned = 1 BEGIN
+    SELECT RAISE(ABORT, 'pinned items cannot be deleted');
+END;
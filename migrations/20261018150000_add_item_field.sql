@@ -0,0 +1,3 @@
+CREATE TABLE IF NOT EXISTS item_field (item_id INTEGER NOT NULL REFERENCES item (id) ON DELETE CASCADE, name TEXT NOT NULL COLLATE NOCASE, value TEXT NOT NULL, PRIMARY KEY (item_id, name, value)) STRICT;
+
+CREATE INDEX IF NOT EXISTS idx_item_field_name_value ON item_field (name, value);
@@ -0,0 +1 @@
+CREATE TABLE IF NOT EXISTS event_type (id INTEGER PRIMARY KEY, event_group TEXT NOT NULL, name TEXT NOT NULL) STRICT;
@@ -0,0 +1,7 @@
+ALTER TABLE item ADD COLUMN valid INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_item_invalid ON item (id) WHERE valid = 0;
+
+CREATE TABLE IF NOT EXISTS item_validation_error (item_id INTEGER NOT NULL REFERENCES item (id) ON DELETE CASCADE, path TEXT NOT NULL, message TEXT NOT NULL) STRICT;
+
+CREATE INDEX IF NOT EXISTS idx_item_validation_error_item_id ON item_validation_error (item_id);
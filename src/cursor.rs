@@ -0,0 +1,151 @@
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::model::ItemFilter;
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct Cursor {
+    #[serde(rename = "f")]
+    pub fingerprint: String,
+    #[serde(default, rename = "b", skip_serializing_if = "is_false")]
+    pub backward: bool,
+    #[serde(rename = "p")]
+    pub position: Vec<CursorValue>,
+}
+
+impl Cursor {
+    pub fn new(filter: &ItemFilter, backward: bool, position: Vec<CursorValue>) -> Self {
+        Self {
+            fingerprint: fingerprint(filter),
+            backward,
+            position,
+        }
+    }
+
+    pub fn decode(cursor: &str, filter: &ItemFilter) -> Result<Self, CursorError> {
+        let cursor: Self = URL_SAFE_NO_PAD
+            .decode(cursor)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .ok_or_else(|| CursorError::new("invalid cursor"))?;
+
+        if cursor.fingerprint != fingerprint(filter) {
+            return Err(CursorError::new("cursor does not match filter"));
+        }
+
+        Ok(cursor)
+    }
+}
+
+impl Display for Cursor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let json = serde_json::to_vec(self).map_err(|_| fmt::Error)?;
+        f.write_str(&URL_SAFE_NO_PAD.encode(json))
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct CursorError(String);
+
+impl CursorError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+impl Display for CursorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for CursorError {}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum CursorValue {
+    Integer(i64),
+    Text(String),
+}
+
+pub fn fingerprint(filter: &ItemFilter) -> String {
+    let json = serde_json::to_vec(&(
+        &filter.query,
+        filter.ignore_case,
+        &filter.system,
+        &filter.r#type,
+        &filter.event_type,
+        &filter.from,
+        &filter.to,
+        filter.pinned,
+        filter.valid,
+        filter.asc,
+        &filter.sort,
+        filter.first_item_id,
+        filter.last_item_id,
+    ))
+    .unwrap_or_default();
+
+    let digest = Sha256::digest(json);
+
+    format!(
+        "{:016x}",
+        u64::from_be_bytes(digest[..8].try_into().unwrap_or_default())
+    )
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn filter(query: &str) -> ItemFilter {
+        serde_json::from_value(serde_json::json!({ "query": query })).unwrap()
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let filter = filter("abc");
+
+        let cursor = Cursor::new(
+            &filter,
+            true,
+            vec![
+                CursorValue::Text("2025-01-01 00:00:00".into()),
+                CursorValue::Integer(42),
+            ],
+        );
+
+        assert_eq!(Cursor::decode(&cursor.to_string(), &filter), Ok(cursor));
+    }
+
+    #[rstest]
+    #[case("", "invalid cursor")]
+    #[case("!!!", "invalid cursor")]
+    #[case("e30", "invalid cursor")]
+    #[case(&Cursor::new(&filter("def"), false, vec![CursorValue::Integer(1)]).to_string(), "cursor does not match filter")]
+    fn test_cursor_decode_errors(#[case] cursor: &str, #[case] expected_error: &str) {
+        assert_eq!(
+            Cursor::decode(cursor, &filter("abc")),
+            Err(CursorError::new(expected_error))
+        );
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        assert_eq!(fingerprint(&filter("abc")), fingerprint(&filter("abc")));
+        assert_ne!(fingerprint(&filter("abc")), fingerprint(&filter("def")));
+        assert_eq!(fingerprint(&filter("abc")), "2ed0f23b7033b414");
+    }
+}
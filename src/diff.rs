@@ -0,0 +1,460 @@
+use std::collections::{BTreeMap, HashMap};
+
+use regex::Regex;
+use serde_json::Value;
+use similar::{ChangeTag, TextDiff};
+use sxd_document::{
+    dom::{ChildOfElement, Element},
+    parser,
+};
+
+use crate::{
+    model::{DiffChange, DiffKind, DiffOp, Item, ItemDiff, ItemHeader},
+    query::wildcard_to_regex,
+};
+
+pub struct DiffIgnore(Vec<Regex>);
+
+impl DiffIgnore {
+    pub fn new(patterns: Option<&str>) -> Self {
+        Self(
+            patterns
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|pattern| !pattern.is_empty())
+                .filter_map(|pattern| {
+                    Regex::new(&format!("(?i)\\A{}\\z", wildcard_to_regex(pattern))).ok()
+                })
+                .collect(),
+        )
+    }
+
+    fn is_match(&self, path: &str, name: &str) -> bool {
+        self.0
+            .iter()
+            .any(|regex| regex.is_match(path) || regex.is_match(name))
+    }
+}
+
+pub fn diff_items(left: &Item, right: &Item, ignore: &DiffIgnore) -> ItemDiff {
+    let (kind, body) = diff_body(&left.body, &right.body, ignore);
+
+    ItemDiff {
+        left: left.summary.id,
+        right: right.summary.id,
+        kind,
+        body,
+        headers: diff_entries(
+            header_entries(&left.headers, ignore),
+            header_entries(&right.headers, ignore),
+        ),
+    }
+}
+
+fn diff_body(left: &[u8], right: &[u8], ignore: &DiffIgnore) -> (DiffKind, Vec<DiffChange>) {
+    if let (Ok(left), Ok(right)) = (
+        serde_json::from_slice::<Value>(left),
+        serde_json::from_slice::<Value>(right),
+    ) {
+        let mut changes = Vec::new();
+        diff_json("$", &left, &right, ignore, &mut changes);
+        return (DiffKind::Json, changes);
+    }
+
+    let left = String::from_utf8_lossy(left);
+    let right = String::from_utf8_lossy(right);
+
+    if let (Some(left), Some(right)) = (xml_entries(&left, ignore), xml_entries(&right, ignore)) {
+        return (DiffKind::Xml, diff_entries(left, right));
+    }
+
+    (DiffKind::Text, diff_text(&left, &right))
+}
+
+fn diff_entries(left: Vec<(String, Value)>, right: Vec<(String, Value)>) -> Vec<DiffChange> {
+    let mut right_values: HashMap<&str, &Value> = right
+        .iter()
+        .map(|(path, value)| (path.as_str(), value))
+        .collect();
+
+    let mut changes = Vec::new();
+
+    for (path, left_value) in &left {
+        match right_values.remove(path.as_str()) {
+            Some(right_value) if right_value == left_value => {}
+            Some(right_value) => changes.push(change(
+                path,
+                DiffOp::Changed,
+                Some(left_value),
+                Some(right_value),
+            )),
+            None => changes.push(change(path, DiffOp::Removed, Some(left_value), None)),
+        }
+    }
+
+    for (path, right_value) in &right {
+        if right_values.contains_key(path.as_str()) {
+            changes.push(change(path, DiffOp::Added, None, Some(right_value)));
+        }
+    }
+
+    changes
+}
+
+fn diff_json(
+    path: &str,
+    left: &Value,
+    right: &Value,
+    ignore: &DiffIgnore,
+    changes: &mut Vec<DiffChange>,
+) {
+    match (left, right) {
+        (Value::Object(left), Value::Object(right)) => {
+            for (key, left_value) in left {
+                let path = json_key_path(path, key);
+
+                if ignore.is_match(&path, key) {
+                    continue;
+                }
+
+                match right.get(key) {
+                    Some(right_value) => diff_json(&path, left_value, right_value, ignore, changes),
+                    None => changes.push(change(&path, DiffOp::Removed, Some(left_value), None)),
+                }
+            }
+
+            for (key, right_value) in right {
+                let path = json_key_path(path, key);
+
+                if !left.contains_key(key) && !ignore.is_match(&path, key) {
+                    changes.push(change(&path, DiffOp::Added, None, Some(right_value)));
+                }
+            }
+        }
+        (Value::Array(left), Value::Array(right)) => {
+            for index in 0..left.len().max(right.len()) {
+                let path = format!("{path}[{index}]");
+
+                match (left.get(index), right.get(index)) {
+                    (Some(left_value), Some(right_value)) => {
+                        diff_json(&path, left_value, right_value, ignore, changes);
+                    }
+                    (Some(left_value), None) => {
+                        changes.push(change(&path, DiffOp::Removed, Some(left_value), None));
+                    }
+                    (None, Some(right_value)) => {
+                        changes.push(change(&path, DiffOp::Added, None, Some(right_value)));
+                    }
+                    (None, None) => {}
+                }
+            }
+        }
+        (left, right) if left != right => {
+            changes.push(change(path, DiffOp::Changed, Some(left), Some(right)));
+        }
+        _ => {}
+    }
+}
+
+fn diff_text(left: &str, right: &str) -> Vec<DiffChange> {
+    TextDiff::from_lines(left, right)
+        .iter_all_changes()
+        .filter_map(|line| {
+            let value = Value::from(line.value().trim_end_matches(['\r', '\n']));
+
+            match line.tag() {
+                ChangeTag::Delete => Some(DiffChange {
+                    path: (line.old_index()? + 1).to_string(),
+                    op: DiffOp::Removed,
+                    left: Some(value),
+                    right: None,
+                }),
+                ChangeTag::Insert => Some(DiffChange {
+                    path: (line.new_index()? + 1).to_string(),
+                    op: DiffOp::Added,
+                    left: None,
+                    right: Some(value),
+                }),
+                ChangeTag::Equal => None,
+            }
+        })
+        .collect()
+}
+
+fn change(path: &str, op: DiffOp, left: Option<&Value>, right: Option<&Value>) -> DiffChange {
+    DiffChange {
+        path: path.into(),
+        op,
+        left: left.cloned(),
+        right: right.cloned(),
+    }
+}
+
+fn header_entries(headers: &[ItemHeader], ignore: &DiffIgnore) -> Vec<(String, Value)> {
+    let mut values: BTreeMap<String, Vec<Value>> = BTreeMap::new();
+
+    for header in headers {
+        let name = header.name.to_lowercase();
+
+        if !ignore.is_match(&name, &name) {
+            values
+                .entry(name)
+                .or_default()
+                .push(String::from_utf8_lossy(&header.value).into());
+        }
+    }
+
+    values
+        .into_iter()
+        .map(|(name, mut values)| {
+            let value = if values.len() == 1 {
+                values.remove(0)
+            } else {
+                Value::Array(values)
+            };
+
+            (name, value)
+        })
+        .collect()
+}
+
+fn json_key_path(path: &str, key: &str) -> String {
+    let is_identifier = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if is_identifier {
+        format!("{path}.{key}")
+    } else {
+        format!("{path}['{}']", key.replace('\'', "\\'"))
+    }
+}
+
+fn xml_entries(text: &str, ignore: &DiffIgnore) -> Option<Vec<(String, Value)>> {
+    let package = parser::parse(text).ok()?;
+    let document = package.as_document();
+    let root = document
+        .root()
+        .children()
+        .into_iter()
+        .find_map(|child| child.element())?;
+    let name = root.name().local_part();
+    let mut entries = Vec::new();
+
+    if !ignore.is_match(&format!("/{name}"), name) {
+        xml_element_entries(&format!("/{name}"), root, ignore, &mut entries);
+    }
+
+    Some(entries)
+}
+
+fn xml_element_entries(
+    path: &str,
+    element: Element<'_>,
+    ignore: &DiffIgnore,
+    entries: &mut Vec<(String, Value)>,
+) {
+    let text: String = element
+        .children()
+        .into_iter()
+        .filter_map(|child| child.text())
+        .map(|text| text.text())
+        .collect();
+
+    entries.push((path.into(), text.trim().into()));
+
+    let mut attributes: Vec<_> = element
+        .attributes()
+        .into_iter()
+        .map(|attribute| (attribute.name().local_part(), attribute.value()))
+        .collect();
+
+    attributes.sort_unstable();
+
+    for (name, value) in attributes {
+        let path = format!("{path}/@{name}");
+
+        if !ignore.is_match(&path, name) {
+            entries.push((path, value.into()));
+        }
+    }
+
+    let children: Vec<Element<'_>> = element
+        .children()
+        .into_iter()
+        .filter_map(|child| match child {
+            ChildOfElement::Element(child) => Some(child),
+            _ => None,
+        })
+        .collect();
+
+    let mut indexes: HashMap<&str, usize> = HashMap::new();
+
+    for child in children {
+        let name = child.name().local_part();
+        let index = indexes.entry(name).or_default();
+        *index += 1;
+
+        let path = if *index == 1 {
+            format!("{path}/{name}")
+        } else {
+            format!("{path}/{name}[{index}]")
+        };
+
+        if !ignore.is_match(&path, name) {
+            xml_element_entries(&path, child, ignore, entries);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use serde_json::json;
+
+    fn added(path: &str, right: Value) -> DiffChange {
+        DiffChange {
+            path: path.into(),
+            op: DiffOp::Added,
+            left: None,
+            right: Some(right),
+        }
+    }
+
+    fn changed(path: &str, left: Value, right: Value) -> DiffChange {
+        DiffChange {
+            path: path.into(),
+            op: DiffOp::Changed,
+            left: Some(left),
+            right: Some(right),
+        }
+    }
+
+    fn removed(path: &str, left: Value) -> DiffChange {
+        DiffChange {
+            path: path.into(),
+            op: DiffOp::Removed,
+            left: Some(left),
+            right: None,
+        }
+    }
+
+    #[rstest]
+    #[case(r#"{"a": 1}"#, r#"{"a": 1}"#, None, vec![])]
+    #[case(r#"{"a": 1, "b": 2}"#, r#"{"a": 3, "c": 4}"#, None, vec![
+        changed("$.a", json!(1), json!(3)),
+        removed("$.b", json!(2)),
+        added("$.c", json!(4)),
+    ])]
+    #[case(r#"{"a": [1, {"b": 2}]}"#, r#"{"a": [1, {"b": 3}, 4]}"#, None, vec![
+        changed("$.a[1].b", json!(2), json!(3)),
+        added("$.a[2]", json!(4)),
+    ])]
+    #[case(r#"{"my key": 1}"#, r#"{"my key": 2}"#, None, vec![
+        changed("$['my key']", json!(1), json!(2)),
+    ])]
+    #[case(r#"{"a": {"timestamp": 1}, "b": 2}"#, r#"{"a": {"timestamp": 2}, "b": 3}"#, Some("timestamp, $.b"), vec![])]
+    #[case(r#"{"createdAt": 1, "updatedAt": 2}"#, r#"{}"#, Some("*at"), vec![])]
+    #[case(r#"[1]"#, r#"{"a": 1}"#, None, vec![
+        changed("$", json!([1]), json!({"a": 1})),
+    ])]
+    fn test_diff_body_json(
+        #[case] left: &str,
+        #[case] right: &str,
+        #[case] ignore: Option<&str>,
+        #[case] expected_changes: Vec<DiffChange>,
+    ) {
+        assert_eq!(
+            diff_body(left.as_bytes(), right.as_bytes(), &DiffIgnore::new(ignore)),
+            (DiffKind::Json, expected_changes)
+        );
+    }
+
+    #[rstest]
+    #[case("<a><b>1</b></a>", "<a><b>1</b></a>", None, vec![])]
+    #[case(r#"<a x="1"><b>1</b><b>2</b></a>"#, r#"<a x="2"><b>1</b><b>3</b><c/></a>"#, None, vec![
+        changed("/a/@x", json!("1"), json!("2")),
+        changed("/a/b[2]", json!("2"), json!("3")),
+        added("/a/c", json!("")),
+    ])]
+    #[case(r#"<s:a xmlns:s="urn:s"><s:b>1</s:b></s:a>"#, "<a><b>2</b></a>", None, vec![
+        changed("/a/b", json!("1"), json!("2")),
+    ])]
+    #[case("<a><b>1</b><Time>1</Time></a>", "<a><c>1</c><Time>2</Time></a>", Some("time,/a/b"), vec![
+        added("/a/c", json!("1")),
+    ])]
+    fn test_diff_body_xml(
+        #[case] left: &str,
+        #[case] right: &str,
+        #[case] ignore: Option<&str>,
+        #[case] expected_changes: Vec<DiffChange>,
+    ) {
+        assert_eq!(
+            diff_body(left.as_bytes(), right.as_bytes(), &DiffIgnore::new(ignore)),
+            (DiffKind::Xml, expected_changes)
+        );
+    }
+
+    #[rstest]
+    #[case("a\nb\n", "a\nb\n", vec![])]
+    #[case("a\nb\nc\n", "a\nx\nc\nd\n", vec![
+        removed("2", json!("b")),
+        added("2", json!("x")),
+        added("4", json!("d")),
+    ])]
+    #[case("<a>", "<b>", vec![removed("1", json!("<a>")), added("1", json!("<b>"))])]
+    fn test_diff_body_text(
+        #[case] left: &str,
+        #[case] right: &str,
+        #[case] expected_changes: Vec<DiffChange>,
+    ) {
+        assert_eq!(
+            diff_body(left.as_bytes(), right.as_bytes(), &DiffIgnore::new(None)),
+            (DiffKind::Text, expected_changes)
+        );
+    }
+
+    #[test]
+    fn test_diff_items_headers() {
+        let item = |id, headers: &[(&str, &str)]| Item {
+            summary: crate::model::ItemSummary {
+                id,
+                ..Default::default()
+            },
+            headers: headers
+                .iter()
+                .map(|(name, value)| ItemHeader::new(name, value.as_bytes()))
+                .collect(),
+            ..Default::default()
+        };
+
+        let left = item(1, &[("a", "1"), ("B", "2"), ("c", "3"), ("date", "x")]);
+        let right = item(
+            2,
+            &[
+                ("a", "1"),
+                ("b", "4"),
+                ("d", "5"),
+                ("d", "6"),
+                ("Date", "y"),
+            ],
+        );
+        let diff = diff_items(&left, &right, &DiffIgnore::new(Some("date")));
+
+        assert_eq!(diff.left, 1);
+        assert_eq!(diff.right, 2);
+
+        assert_eq!(
+            diff.headers,
+            vec![
+                changed("b", json!("2"), json!("4")),
+                removed("c", json!("3")),
+                added("d", json!(["5", "6"])),
+            ]
+        );
+    }
+}
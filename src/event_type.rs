@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+
+use anyhow::{Result, anyhow};
+
+use crate::model::EventTypeGroup;
+
+pub struct EventTypes {
+    groups: Vec<EventTypeGroup>,
+    names: HashMap<i64, (usize, usize)>,
+}
+
+impl EventTypes {
+    pub fn builtin() -> Result<Self> {
+        Self::parse(include_str!("../event.types.json"))
+    }
+
+    pub fn get(&self, id: i64) -> Option<(&str, &str)> {
+        self.names.get(&id).map(|&(group, event_type)| {
+            let group = &self.groups[group];
+            (group.name.as_str(), group.types[event_type].name.as_str())
+        })
+    }
+
+    pub fn groups(&self) -> &[EventTypeGroup] {
+        &self.groups
+    }
+
+    pub fn parse(json: &str) -> Result<Self> {
+        let groups: Vec<EventTypeGroup> = serde_json::from_str(json)?;
+        let mut names = HashMap::new();
+
+        for (i, group) in groups.iter().enumerate() {
+            if group.name.trim().is_empty() {
+                return Err(anyhow!("event group name must not be empty"));
+            }
+
+            for (j, event_type) in group.types.iter().enumerate() {
+                if event_type.name.trim().is_empty() {
+                    return Err(anyhow!(
+                        "event type name must not be empty in group '{}'",
+                        group.name
+                    ));
+                }
+
+                if names.insert(event_type.id, (i, j)).is_some() {
+                    return Err(anyhow!("duplicate event type id {}", event_type.id));
+                }
+            }
+        }
+
+        Ok(Self { groups, names })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("", false)]
+    #[case("{}", false)]
+    #[case("[]", true)]
+    #[case(r#"[{"name": "A", "types": [{"name": "B", "id": 1}]}]"#, true)]
+    #[case(r#"[{"name": " ", "types": []}]"#, false)]
+    #[case(r#"[{"name": "A", "types": [{"name": "", "id": 1}]}]"#, false)]
+    #[case(
+        r#"[{"name": "A", "types": [{"name": "B", "id": 1}]}, {"name": "C", "types": [{"name": "D", "id": 1}]}]"#,
+        false
+    )]
+    fn test_parse(#[case] json: &str, #[case] expected_valid: bool) {
+        assert_eq!(EventTypes::parse(json).is_ok(), expected_valid);
+    }
+
+    #[test]
+    fn test_builtin() -> Result<()> {
+        let event_types = EventTypes::builtin()?;
+
+        assert_eq!(
+            event_types.get(91),
+            Some(("APPIAN_CASE", "APPIAN_SEND_PAYLOAD"))
+        );
+        assert_eq!(event_types.get(0), None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,206 @@
+use anyhow::{Result, anyhow};
+use regex::bytes::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json_path::JsonPath;
+use sxd_document::Package;
+use sxd_xpath::{Context, Value as XPathValue};
+
+use crate::{model::ItemField, xml};
+
+pub struct FieldExtractors(Vec<(String, FieldExtractor)>);
+
+impl FieldExtractors {
+    pub fn builtin() -> Result<Self> {
+        Self::parse(include_str!("../field.extractors.json"))
+    }
+
+    pub fn extract(&self, body: &[u8]) -> Vec<ItemField> {
+        let mut json = None;
+        let mut xml = None;
+        let mut fields: Vec<ItemField> = Vec::new();
+
+        for (name, extractor) in &self.0 {
+            let values = match extractor {
+                FieldExtractor::JsonPath(path) => json
+                    .get_or_insert_with(|| serde_json::from_slice::<Value>(body).ok())
+                    .as_ref()
+                    .map(|json| {
+                        path.query(json)
+                            .into_iter()
+                            .filter_map(|value| match value {
+                                Value::Bool(_) | Value::Number(_) => Some(value.to_string()),
+                                Value::String(text) => Some(text.clone()),
+                                _ => None,
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                FieldExtractor::Regex(regex) => regex
+                    .captures_iter(body)
+                    .filter_map(|captures| captures.get(1).or_else(|| captures.get(0)))
+                    .map(|group| String::from_utf8_lossy(group.as_bytes()).into_owned())
+                    .collect(),
+                FieldExtractor::XPath(path) => xml
+                    .get_or_insert_with(|| str::from_utf8(body).ok().and_then(xml::parse))
+                    .as_ref()
+                    .map(|package| evaluate_xpath(package, path))
+                    .unwrap_or_default(),
+            };
+
+            for value in values {
+                let value = value.trim();
+
+                if !value.is_empty()
+                    && !fields
+                        .iter()
+                        .any(|field| field.name == *name && field.value == value)
+                {
+                    fields.push(ItemField {
+                        name: name.clone(),
+                        value: value.into(),
+                    });
+                }
+            }
+        }
+
+        fields
+    }
+
+    pub fn parse(json: &str) -> Result<Self> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase", tag = "source")]
+        enum FieldExtractorConfig {
+            JsonPath {
+                path: String,
+            },
+            Regex {
+                regex: String,
+            },
+            #[serde(rename = "xpath")]
+            XPath {
+                path: String,
+            },
+        }
+
+        #[derive(Deserialize)]
+        struct FieldConfig {
+            name: String,
+            #[serde(flatten)]
+            extractor: FieldExtractorConfig,
+        }
+
+        let configs: Vec<FieldConfig> = serde_json::from_str(json)?;
+
+        configs
+            .into_iter()
+            .map(|config| {
+                if config.name.trim().is_empty() {
+                    return Err(anyhow!("field name must not be empty"));
+                }
+
+                let extractor = match config.extractor {
+                    FieldExtractorConfig::JsonPath { path } => {
+                        FieldExtractor::JsonPath(JsonPath::parse(&path)?)
+                    }
+                    FieldExtractorConfig::Regex { regex } => {
+                        FieldExtractor::Regex(Regex::new(&regex)?)
+                    }
+                    FieldExtractorConfig::XPath { path } => {
+                        if !xml::with_xpath(&path, |xpath| xpath.is_some())? {
+                            return Err(anyhow!("missing XPath for field '{}'", config.name));
+                        }
+
+                        FieldExtractor::XPath(path)
+                    }
+                };
+
+                Ok((config.name, extractor))
+            })
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+}
+
+enum FieldExtractor {
+    JsonPath(JsonPath),
+    Regex(Regex),
+    XPath(String),
+}
+
+fn evaluate_xpath(package: &Package, path: &str) -> Vec<String> {
+    let document = package.as_document();
+
+    let Ok(Some(value)) = xml::with_xpath(path, |xpath| {
+        xpath.map(|xpath| xpath.evaluate(&Context::new(), document.root()))
+    }) else {
+        return Vec::new();
+    };
+
+    match value {
+        Ok(XPathValue::Nodeset(nodes)) => nodes
+            .document_order()
+            .iter()
+            .map(|node| node.string_value())
+            .collect(),
+        Ok(XPathValue::Boolean(boolean)) => vec![boolean.to_string()],
+        Ok(XPathValue::Number(number)) => vec![number.to_string()],
+        Ok(XPathValue::String(text)) => vec![text],
+        Err(_) => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("", false)]
+    #[case("[]", true)]
+    #[case(r#"[{"name": "a", "source": "regex", "regex": "("}]"#, false)]
+    #[case(r#"[{"name": "a", "source": "jsonPath", "path": "x"}]"#, false)]
+    #[case(r#"[{"name": "a", "source": "xpath", "path": "//["}]"#, false)]
+    #[case(r#"[{"name": " ", "source": "regex", "regex": "a"}]"#, false)]
+    #[case(r#"[{"name": "a", "source": "xpath", "path": "//a"}]"#, true)]
+    fn test_parse(#[case] json: &str, #[case] expected_valid: bool) {
+        assert_eq!(FieldExtractors::parse(json).is_ok(), expected_valid);
+    }
+
+    #[test]
+    fn test_builtin() {
+        assert!(FieldExtractors::builtin().is_ok());
+    }
+
+    #[rstest]
+    #[case(br#"{"entity": {"vacancyId": 42}}"#, &[("vacancyId", "42")])]
+    #[case(br#"{"a": [{"vacancyId": "1"}, {"vacancyId": "2"}, {"vacancyId": "1"}]}"#, &[("vacancyId", "1"), ("vacancyId", "2")])]
+    #[case(br#"<t:a xmlns:t="urn:t"><t:vacancyId> 43 </t:vacancyId></t:a>"#, &[("vacancyId", "43")])]
+    #[case(b"person=7 person=8", &[("personId", "7"), ("personId", "8")])]
+    #[case(b"", &[])]
+    fn test_extract(#[case] body: &[u8], #[case] expected_fields: &[(&str, &str)]) -> Result<()> {
+        let extractors = FieldExtractors::parse(
+            r#"[
+                {"name": "vacancyId", "source": "jsonPath", "path": "$..vacancyId"},
+                {"name": "vacancyId", "source": "xpath", "path": "//vacancyId"},
+                {"name": "personId", "source": "regex", "regex": "person=(\\d+)"}
+            ]"#,
+        )?;
+
+        let fields: Vec<_> = extractors
+            .extract(body)
+            .into_iter()
+            .map(|field| (field.name, field.value))
+            .collect();
+
+        assert_eq!(
+            fields,
+            expected_fields
+                .iter()
+                .map(|(name, value)| ((*name).to_string(), (*value).to_string()))
+                .collect::<Vec<_>>()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,242 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use regex::bytes::{Regex, RegexSet, SetMatches};
+use serde::Deserialize;
+
+use crate::model::{ItemType, NewItemHeader};
+
+pub struct ItemTypes {
+    rules: Vec<ItemTypeRule>,
+    body_regexes: RegexSet,
+    types: Vec<ItemType>,
+}
+
+impl ItemTypes {
+    pub fn builtin() -> Result<Self> {
+        Self::parse(include_str!("../item.types.json"))
+    }
+
+    pub fn get_item_type(
+        &self,
+        path: Option<&str>,
+        headers: &[NewItemHeader<'_>],
+        body: &[u8],
+    ) -> Option<&str> {
+        let message = Message {
+            path,
+            headers,
+            body_matches: self.body_regexes.matches(body),
+        };
+
+        self.rules
+            .iter()
+            .find(|rule| {
+                rule.conditions.is_match(&message)
+                    && !rule.not.iter().any(|not| not.is_match(&message))
+            })
+            .map(|rule| rule.key.as_str())
+    }
+
+    pub fn parse(json: &str) -> Result<Self> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct ConditionsConfig {
+            #[serde(default)]
+            matches: Vec<String>,
+            #[serde(default)]
+            headers: BTreeMap<String, String>,
+            path: Option<String>,
+            content_type: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        struct ItemTypeConfig {
+            key: String,
+            name: Option<String>,
+            color: Option<String>,
+            #[serde(flatten)]
+            conditions: ConditionsConfig,
+            #[serde(default)]
+            not: Vec<ConditionsConfig>,
+        }
+
+        let configs: Vec<ItemTypeConfig> = serde_json::from_str(json)?;
+        let mut body_patterns = Vec::new();
+
+        let mut conditions = |config: ConditionsConfig| -> Result<Conditions> {
+            let body = config
+                .matches
+                .into_iter()
+                .map(|pattern| {
+                    body_patterns.push(pattern);
+                    body_patterns.len() - 1
+                })
+                .collect();
+
+            let headers = config
+                .headers
+                .into_iter()
+                .map(|(name, pattern)| Ok((name.to_lowercase(), Regex::new(&pattern)?)))
+                .collect::<Result<_>>()?;
+
+            let path = config.path.as_deref().map(Regex::new).transpose()?;
+
+            let content_type = config
+                .content_type
+                .map(|pattern| Regex::new(&format!("(?i){pattern}")))
+                .transpose()?;
+
+            Ok(Conditions {
+                body,
+                headers,
+                path,
+                content_type,
+            })
+        };
+
+        let mut rules = Vec::with_capacity(configs.len());
+        let mut types = Vec::with_capacity(configs.len());
+
+        for config in configs {
+            types.push(ItemType {
+                name: config.name.unwrap_or_else(|| config.key.clone()),
+                key: config.key.clone(),
+                color: config.color,
+            });
+
+            rules.push(ItemTypeRule {
+                key: config.key,
+                conditions: conditions(config.conditions)?,
+                not: config
+                    .not
+                    .into_iter()
+                    .map(&mut conditions)
+                    .collect::<Result<_>>()?,
+            });
+        }
+
+        Ok(Self {
+            rules,
+            body_regexes: RegexSet::new(body_patterns)?,
+            types,
+        })
+    }
+
+    pub fn types(&self) -> &[ItemType] {
+        &self.types
+    }
+}
+
+struct Conditions {
+    body: Vec<usize>,
+    headers: Vec<(String, Regex)>,
+    path: Option<Regex>,
+    content_type: Option<Regex>,
+}
+
+impl Conditions {
+    fn is_match(&self, message: &Message<'_>) -> bool {
+        self.body
+            .iter()
+            .all(|&index| message.body_matches.matched(index))
+            && self
+                .headers
+                .iter()
+                .all(|(name, regex)| message.is_header_match(name, regex))
+            && self.path.as_ref().is_none_or(|regex| {
+                message
+                    .path
+                    .is_some_and(|path| regex.is_match(path.as_bytes()))
+            })
+            && self
+                .content_type
+                .as_ref()
+                .is_none_or(|regex| message.is_header_match("content-type", regex))
+    }
+}
+
+struct ItemTypeRule {
+    key: String,
+    conditions: Conditions,
+    not: Vec<Conditions>,
+}
+
+struct Message<'a> {
+    path: Option<&'a str>,
+    headers: &'a [NewItemHeader<'a>],
+    body_matches: SetMatches,
+}
+
+impl Message<'_> {
+    fn is_header_match(&self, name: &str, regex: &Regex) -> bool {
+        self.headers
+            .iter()
+            .any(|header| header.name.eq_ignore_ascii_case(name) && regex.is_match(header.value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("", false)]
+    #[case("{}", false)]
+    #[case(r#"[{"key": "a", "matches": ["("]}]"#, false)]
+    #[case(r#"[{"key": "a", "headers": {"soapaction": "("}}]"#, false)]
+    #[case(r#"[{"key": "a", "not": [{"path": "("}]}]"#, false)]
+    #[case(r#"[{"key": "a", "matches": ["a"]}]"#, true)]
+    #[case(
+        r#"[{"key": "a", "name": "A", "color": "red", "contentType": "json"}]"#,
+        true
+    )]
+    fn test_parse(#[case] json: &str, #[case] expected_valid: bool) {
+        assert_eq!(ItemTypes::parse(json).is_ok(), expected_valid);
+    }
+
+    #[rstest]
+    #[case(None, &[], b"ab", Some("ab"))]
+    #[case(None, &[], b"a", Some("a"))]
+    #[case(None, &[], b"ac", Some("ac"))]
+    #[case(None, &[], b"b", None)]
+    #[case(None, &[("SOAPAction", "urn:update")], b"", Some("soap"))]
+    #[case(None, &[("soapaction", "urn:create")], b"", None)]
+    #[case(Some("/sink/folders/1"), &[], b"", Some("folder"))]
+    #[case(Some("/sink/other"), &[], b"", None)]
+    #[case(None, &[("Content-Type", "Application/JSON; charset=utf-8")], b"", Some("json"))]
+    #[case(None, &[("content-type", "application/json"), ("x-test", "1")], b"", None)]
+    fn test_get_item_type(
+        #[case] path: Option<&str>,
+        #[case] headers: &[(&str, &str)],
+        #[case] body: &[u8],
+        #[case] expected_item_type: Option<&str>,
+    ) -> Result<()> {
+        let item_types = ItemTypes::parse(
+            r#"[
+                {"key": "ab", "matches": ["a", "b"]},
+                {"key": "a", "matches": ["a"], "not": [{"matches": ["c"]}]},
+                {"key": "ac", "matches": ["a"]},
+                {"key": "soap", "headers": {"SOAPAction": "update"}},
+                {"key": "folder", "path": "^/sink/folders/"},
+                {"key": "json", "contentType": "^application/json", "not": [{"headers": {"x-test": "."}}]}
+            ]"#,
+        )?;
+
+        let headers: Vec<_> = headers
+            .iter()
+            .map(|(name, value)| NewItemHeader {
+                name,
+                value: value.as_bytes(),
+            })
+            .collect();
+
+        assert_eq!(
+            item_types.get_item_type(path, &headers, body),
+            expected_item_type
+        );
+
+        Ok(())
+    }
+}
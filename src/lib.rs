@@ -2,7 +2,17 @@
 #![allow(clippy::missing_safety_doc)]
 #![allow(clippy::must_use_candidate)]
 
+pub mod cursor;
+pub mod diff;
+pub mod event_type;
+pub mod field;
+pub mod item_type;
 pub mod model;
+pub mod query;
+pub mod reload;
 pub mod repository;
+pub mod schema;
 pub mod server;
 pub mod service;
+pub mod system;
+pub mod xml;
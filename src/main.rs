@@ -4,8 +4,14 @@ use std::{
 };
 
 use anyhow::Result;
-use clap::Parser;
-use sink::{repository::open_repository, server::start, service::new_service};
+use axum::{extract::Query, http::Uri};
+use clap::{Parser, Subcommand};
+use sink::{
+    model::{ItemFilter, ReclassifyParams},
+    repository::open_repository,
+    server::start,
+    service::{Service, ServiceOptions, new_service_with_options},
+};
 use tracing::info;
 use tracing_subscriber::{EnvFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -23,6 +29,43 @@ struct Args {
     /// Database file to use
     #[arg(default_value = "sink.db", long)]
     db: PathBuf,
+
+    /// Event type catalog file to use instead of the built-in catalog, reloaded on change
+    #[arg(long)]
+    event_types: Option<PathBuf>,
+
+    /// Field extractors file to use instead of the built-in extractors, reloaded on change
+    #[arg(long)]
+    field_extractors: Option<PathBuf>,
+
+    /// Item JSON Schemas file, keyed by item type, reloaded on change
+    #[arg(long)]
+    item_schemas: Option<PathBuf>,
+
+    /// Item type rules file to use instead of the built-in rules, reloaded on change
+    #[arg(long)]
+    item_types: Option<PathBuf>,
+
+    /// System extraction rules file to use instead of the built-in rules, reloaded on change
+    #[arg(long)]
+    system_rules: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Re-run system, type, entity event and field extraction over stored items
+    Reclassify {
+        /// Item filter as a query string, e.g. "system=abc&query=type:none"
+        #[arg(default_value = "")]
+        filter: String,
+
+        /// Report changes without saving them
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 #[tokio::main]
@@ -39,7 +82,36 @@ async fn main() -> Result<()> {
     info!(version = VERSION, ?args, "starting");
 
     let repository = open_repository(args.db).await?;
-    let service = new_service(repository)?;
+    let service = new_service_with_options(
+        repository,
+        ServiceOptions {
+            event_types: args.event_types,
+            field_extractors: args.field_extractors,
+            item_schemas: args.item_schemas,
+            item_types: args.item_types,
+            system_rules: args.system_rules,
+        },
+    )
+    .await?;
+
+    match args.command {
+        Some(Command::Reclassify { filter, dry_run }) => {
+            let Query(filter) =
+                Query::<ItemFilter>::try_from_uri(&format!("/?{filter}").parse::<Uri>()?)?;
+
+            let result = service
+                .reclassify_items(
+                    &filter,
+                    &ReclassifyParams {
+                        dry_run: Some(dry_run),
+                    },
+                )
+                .await?;
+
+            println!("{}", serde_json::to_string_pretty(&result)?);
 
-    start(&args.host, args.port, service).await
+            Ok(())
+        }
+        None => start(&args.host, args.port, service).await,
+    }
 }
@@ -1,17 +1,126 @@
-use serde::{Deserialize, Serialize, Serializer};
-use sqlx::FromRow;
+use std::{
+    error,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use anyhow::{Error, anyhow};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use serde_json::Value;
+use sqlx::{FromRow, types::Json};
 
 pub const CONTENT_TYPE: &str = "content-type";
 pub const X_RESPONSE_HEADER_PREFIX: &str = "x-response-header-";
 
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CountMode {
+    #[default]
+    Exact,
+    Estimated,
+    None,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct DiffChange {
+    pub path: String,
+    pub op: DiffOp,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub left: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub right: Option<Value>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffKind {
+    Json,
+    Text,
+    Xml,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffOp {
+    Added,
+    Changed,
+    Removed,
+}
+
+#[derive(Deserialize)]
+pub struct DiffParams {
+    pub left: i64,
+    pub right: i64,
+    pub ignore: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct EventType {
+    pub name: String,
+    pub id: i64,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct EventTypeGroup {
+    pub name: String,
+    pub types: Vec<EventType>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct FacetCount {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    pub count: i32,
+}
+
+#[derive(Debug, FromRow, PartialEq)]
+pub struct FacetValue {
+    pub facet: String,
+    pub value: Option<String>,
+    pub count: i32,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HistogramBucket {
+    Minute,
+    #[default]
+    Hour,
+    Day,
+}
+
+#[derive(Debug, FromRow, PartialEq, Serialize)]
+pub struct HistogramCount {
+    pub start: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    pub count: i32,
+}
+
+#[derive(Deserialize)]
+pub struct HistogramParams {
+    pub bucket: Option<HistogramBucket>,
+    pub split: Option<HistogramSplit>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HistogramSplit {
+    System,
+    Type,
+}
+
 #[derive(Default, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Item {
     #[serde(flatten)]
     pub summary: ItemSummary,
     pub headers: Vec<ItemHeader>,
+    pub fields: Vec<ItemField>,
+    pub validation_errors: Vec<ItemValidationError>,
     #[serde(serialize_with = "bytes_as_string")]
     pub body: Vec<u8>,
+    pub notes: Vec<ItemNote>,
 }
 
 impl Item {
@@ -35,20 +144,93 @@ impl Item {
     }
 }
 
-#[derive(Deserialize)]
+#[derive(Debug, Default, PartialEq)]
+pub struct ItemClassification {
+    pub system: Option<String>,
+    pub r#type: Option<String>,
+    pub entity_event_id: Option<i64>,
+    pub fields: Vec<ItemField>,
+    pub valid: Option<bool>,
+    pub validation_errors: Vec<ItemValidationError>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemDiff {
+    pub left: i64,
+    pub right: i64,
+    pub kind: DiffKind,
+    pub body: Vec<DiffChange>,
+    pub headers: Vec<DiffChange>,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemFacets {
+    pub system: Vec<FacetCount>,
+    pub r#type: Vec<FacetCount>,
+    pub event_group: Vec<FacetCount>,
+    pub user_agent: Vec<FacetCount>,
+}
+
+#[derive(Clone, Debug, FromRow, PartialEq, Serialize)]
+pub struct ItemField {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Clone, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ItemFilter {
     pub query: Option<String>,
+    pub ignore_case: Option<bool>,
     pub system: Option<String>,
     pub r#type: Option<String>,
     pub event_type: Option<String>,
     pub from: Option<String>,
     pub to: Option<String>,
+    pub pinned: Option<bool>,
+    pub valid: Option<bool>,
     pub asc: Option<bool>,
+    #[serde(default, deserialize_with = "deserialize_sort")]
+    pub sort: Option<Vec<SortField>>,
+    pub after_item_id: Option<i64>,
+    pub cursor: Option<String>,
     pub first_item_id: Option<i64>,
     pub last_item_id: Option<i64>,
     pub batch_size: Option<u32>,
+    pub count: Option<CountMode>,
+    pub facets: Option<bool>,
     pub load_first_item: Option<bool>,
+    pub saved: Option<String>,
+}
+
+impl ItemFilter {
+    #[must_use]
+    pub fn or(self, other: Self) -> Self {
+        Self {
+            query: self.query.or(other.query),
+            ignore_case: self.ignore_case.or(other.ignore_case),
+            system: self.system.or(other.system),
+            r#type: self.r#type.or(other.r#type),
+            event_type: self.event_type.or(other.event_type),
+            from: self.from.or(other.from),
+            to: self.to.or(other.to),
+            pinned: self.pinned.or(other.pinned),
+            valid: self.valid.or(other.valid),
+            asc: self.asc.or(other.asc),
+            sort: self.sort.or(other.sort),
+            after_item_id: self.after_item_id.or(other.after_item_id),
+            cursor: self.cursor.or(other.cursor),
+            first_item_id: self.first_item_id.or(other.first_item_id),
+            last_item_id: self.last_item_id.or(other.last_item_id),
+            batch_size: self.batch_size.or(other.batch_size),
+            count: self.count.or(other.count),
+            facets: self.facets.or(other.facets),
+            load_first_item: self.load_first_item.or(other.load_first_item),
+            saved: self.saved.or(other.saved),
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -67,14 +249,52 @@ impl ItemHeader {
     }
 }
 
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemNote {
+    pub id: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    pub text: String,
+    pub created_date: String,
+}
+
+pub struct ItemPage {
+    pub items: Vec<ItemSummary>,
+    pub total_items: Option<i32>,
+    pub total_items_capped: bool,
+    pub next: Option<String>,
+    pub prev: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemRelationship {
+    Acknowledgement,
+    Notification,
+    #[default]
+    Other,
+    Payload,
+    Redelivery,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ItemSearchResult {
     pub items: Vec<ItemSummary>,
-    pub total_items: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_items: Option<i32>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub total_items_capped: bool,
     pub systems: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub first_item: Option<Item>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facets: Option<ItemFacets>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev: Option<String>,
 }
 
 #[derive(Default, FromRow, Serialize)]
@@ -90,17 +310,224 @@ pub struct ItemSummary {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub entity_event_id: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_group: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub user_agent: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
     pub submit_date: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid: Option<bool>,
+    pub tags: Json<Vec<String>>,
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ItemType {
+    pub key: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+#[derive(Clone, Debug, FromRow, PartialEq, Serialize)]
+pub struct ItemValidationError {
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ReclassifiedItem {
+    pub id: i64,
+    pub changes: Vec<DiffChange>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReclassifyParams {
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReclassifyResult {
+    pub dry_run: bool,
+    pub processed: i32,
+    pub changed: i32,
+    pub items: Vec<ReclassifiedItem>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelatedItem {
+    #[serde(flatten)]
+    pub summary: ItemSummary,
+    pub relationship: ItemRelationship,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedSearch {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub filter: Value,
+    pub created_date: String,
+    pub updated_date: String,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize)]
+pub enum SortKey {
+    EntityEventId,
+    EventId,
+    Id,
+    Size,
+    SubmitDate,
+    System,
+    Type,
+    UserAgent,
+}
+
+impl FromStr for SortKey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let key = match s.replace(['_', '-'], "").to_ascii_lowercase().as_str() {
+            "entityeventid" => Self::EntityEventId,
+            "eventid" => Self::EventId,
+            "id" => Self::Id,
+            "size" => Self::Size,
+            "date" | "submitdate" => Self::SubmitDate,
+            "system" => Self::System,
+            "type" => Self::Type,
+            "useragent" => Self::UserAgent,
+            _ => return Err(anyhow!("unknown sort key '{s}'")),
+        };
+
+        Ok(key)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize)]
+pub struct SortField {
+    pub key: SortKey,
+    pub descending: bool,
+}
+
+impl FromStr for SortField {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, descending) = match s.strip_prefix('-') {
+            Some(key) => (key, true),
+            None => (s.strip_prefix('+').unwrap_or(s), false),
+        };
+
+        Ok(Self {
+            key: key.parse()?,
+            descending,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Stats {
+    pub total_items: i64,
+    pub total_bytes: i64,
+    pub items_last_hour: i64,
+    pub systems: Vec<SystemStats>,
+    pub types: Vec<TypeStats>,
+    pub user_agents: Vec<UserAgentStats>,
+}
+
+#[derive(Debug, FromRow, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemStats {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+    pub count: i64,
+    pub average_size: f64,
+    pub first_seen: String,
+    pub last_seen: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Timeline {
+    pub event_id: i64,
+    pub entity_event_id: Option<i64>,
+    pub event_type: Option<String>,
+    pub event_group: Option<String>,
+    pub steps: Vec<TimelineStep>,
+    pub missing: Vec<ItemRelationship>,
+}
+
+#[derive(FromRow, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineStep {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub summary: ItemSummary,
+    #[sqlx(skip)]
+    pub relationship: ItemRelationship,
+    pub gap_seconds: Option<i64>,
+}
+
+#[derive(Debug, FromRow, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeStats {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<String>,
+    pub count: i64,
+    pub average_size: f64,
+}
+
+#[derive(Debug, FromRow, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserAgentStats {
+    pub user_agent: String,
+    pub count: i64,
+}
+
+#[derive(Debug)]
+pub struct NotFoundError(pub String);
+
+impl Display for NotFoundError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl error::Error for NotFoundError {}
+
+#[derive(Debug)]
+pub struct ValidationError(pub String);
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl error::Error for ValidationError {}
+
 pub struct NewItem<'a> {
     pub system: Option<&'a str>,
     pub r#type: Option<&'a str>,
     pub event_id: Option<i64>,
     pub entity_event_id: Option<i64>,
     pub user_agent: Option<&'a str>,
+    pub path: Option<&'a str>,
     pub headers: &'a [NewItemHeader<'a>],
+    pub fields: &'a [ItemField],
+    pub valid: Option<bool>,
+    pub validation_errors: &'a [ItemValidationError],
     pub body: &'a [u8],
 }
 
@@ -109,6 +536,36 @@ pub struct NewItemHeader<'a> {
     pub value: &'a [u8],
 }
 
+#[derive(Deserialize)]
+pub struct NewItemNote {
+    pub author: Option<String>,
+    pub text: String,
+}
+
+#[derive(Deserialize)]
+pub struct NewSavedSearch {
+    pub owner: Option<String>,
+    pub description: Option<String>,
+    pub filter: Value,
+}
+
+fn deserialize_sort<'de, D>(deserializer: D) -> Result<Option<Vec<SortField>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|field| !field.is_empty())
+                .map(str::parse)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(de::Error::custom)
+        })
+        .transpose()
+}
+
 fn bytes_as_string<S>(bytes: &[u8], s: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
@@ -119,6 +576,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::rstest;
 
     #[test]
     fn test_item_content_type() {
@@ -137,6 +595,18 @@ mod tests {
         assert_eq!(item.content_type(), Some(CONTENT_TYPE_VALUE));
     }
 
+    #[rstest]
+    #[case("id", Some(SortField { key: SortKey::Id, descending: false }))]
+    #[case("+size", Some(SortField { key: SortKey::Size, descending: false }))]
+    #[case("-submit_date", Some(SortField { key: SortKey::SubmitDate, descending: true }))]
+    #[case("-submitDate", Some(SortField { key: SortKey::SubmitDate, descending: true }))]
+    #[case("entity-event-id", Some(SortField { key: SortKey::EntityEventId, descending: false }))]
+    #[case("body", None)]
+    #[case("", None)]
+    fn test_sort_field_from_str(#[case] value: &str, #[case] expected_field: Option<SortField>) {
+        assert_eq!(value.parse::<SortField>().ok(), expected_field);
+    }
+
     #[test]
     fn test_item_x_response_headers() {
         const X_HEADER_NAME_1: &str = "header-1";
@@ -0,0 +1,905 @@
+use std::{
+    borrow::Cow,
+    error::Error,
+    fmt::{self, Display, Formatter},
+    iter::Peekable,
+    ops::Bound,
+    str::CharIndices,
+};
+
+use regex::{Regex, escape};
+use serde_json_path::JsonPath;
+use sxd_xpath::Factory;
+
+const MAX_QUERY_DEPTH: usize = 64;
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum QueryExpression<'a> {
+    And(Vec<QueryExpression<'a>>),
+    Date(Range<String>),
+    EntityEventId(Range<i64>),
+    Event(Pattern<'a>),
+    EventId(Range<i64>),
+    Field(&'a str, Option<Pattern<'a>>),
+    Header(&'a str, Option<Pattern<'a>>),
+    Id(Range<i64>),
+    Json(&'a str, Option<Pattern<'a>>),
+    Not(Box<QueryExpression<'a>>),
+    Or(Vec<QueryExpression<'a>>),
+    Regex(&'a str),
+    Size(Range<i64>),
+    System(Pattern<'a>),
+    Tag(Pattern<'a>),
+    Text(&'a str),
+    Type(Pattern<'a>),
+    UserAgent(Pattern<'a>),
+    XPath(&'a str, Option<Pattern<'a>>),
+}
+
+impl<'a> QueryExpression<'a> {
+    fn parse_term(term: &'a str, position: usize) -> Result<Self, QueryError> {
+        let Some((name, value)) = term.split_once(':') else {
+            return Ok(QueryExpression::Text(term));
+        };
+
+        let invalid = |kind| QueryError::new(format!("invalid {kind} '{value}'"), position);
+
+        let expression = match name {
+            "body" => QueryExpression::Text(value),
+            "date" => QueryExpression::Date(
+                Range::parse(value, parse_date).ok_or_else(|| invalid("date"))?,
+            ),
+            "entity-event-id" => QueryExpression::EntityEventId(
+                Range::parse(value, parse_number).ok_or_else(|| invalid("number"))?,
+            ),
+            "event" => QueryExpression::Event(Pattern::new(value)),
+            "event-id" => QueryExpression::EventId(
+                Range::parse(value, parse_number).ok_or_else(|| invalid("number"))?,
+            ),
+            "field" => {
+                let (name, pattern) = match split_predicate(value, position)? {
+                    (name, Some(Pattern::Exact(value))) => (name, Some(Pattern::new(value))),
+                    predicate => predicate,
+                };
+
+                if name.is_empty() {
+                    return Err(QueryError::new("missing field name", position));
+                }
+
+                QueryExpression::Field(name, pattern)
+            }
+            "header" => {
+                let (name, pattern) = match split_predicate(value, position)? {
+                    (name, Some(Pattern::Exact(value))) => (name, Some(Pattern::new(value))),
+                    predicate => predicate,
+                };
+
+                if name.is_empty() {
+                    return Err(QueryError::new("missing header name", position));
+                }
+
+                QueryExpression::Header(name, pattern)
+            }
+            "id" => QueryExpression::Id(
+                Range::parse(value, parse_number).ok_or_else(|| invalid("number"))?,
+            ),
+            "json" => {
+                let (path, pattern) = split_predicate(value, position)?;
+
+                JsonPath::parse(path).map_err(|e| {
+                    QueryError::new(format!("invalid JSONPath '{path}': {e}"), position)
+                })?;
+
+                QueryExpression::Json(path, pattern)
+            }
+            "regex" => {
+                Regex::new(value).map_err(|_| invalid("regex"))?;
+
+                QueryExpression::Regex(value)
+            }
+            "size" => QueryExpression::Size(
+                Range::parse(value, parse_size).ok_or_else(|| invalid("size"))?,
+            ),
+            "system" => QueryExpression::System(Pattern::new(value)),
+            "tag" => QueryExpression::Tag(Pattern::new(value)),
+            "type" => QueryExpression::Type(Pattern::new(value)),
+            "user-agent" => QueryExpression::UserAgent(Pattern::new(value)),
+            "xpath" => {
+                let (path, pattern) = split_predicate(value, position)?;
+
+                match Factory::new().build(path) {
+                    Ok(Some(_)) => QueryExpression::XPath(path, pattern),
+                    Ok(None) => {
+                        return Err(QueryError::new("missing XPath", position));
+                    }
+                    Err(e) => {
+                        return Err(QueryError::new(
+                            format!("invalid XPath '{path}': {e}"),
+                            position,
+                        ));
+                    }
+                }
+            }
+            _ => QueryExpression::Header(name, Some(Pattern::Contains(value))),
+        };
+
+        Ok(expression)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Pattern<'a> {
+    Contains(&'a str),
+    Exact(&'a str),
+    Regex(&'a str),
+    Wildcard(&'a str),
+}
+
+impl<'a> Pattern<'a> {
+    fn new(value: &'a str) -> Self {
+        if value.contains(['*', '?']) {
+            Pattern::Wildcard(value)
+        } else {
+            Pattern::Exact(value)
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Range<T> {
+    pub lower: Bound<T>,
+    pub upper: Bound<T>,
+}
+
+impl<T> Range<T> {
+    fn parse(value: &str, parse: impl Fn(&str) -> Option<(T, T)>) -> Option<Self> {
+        let (lower, upper) = if let Some(value) = value.strip_prefix(">=") {
+            (Bound::Included(parse(value)?.0), Bound::Unbounded)
+        } else if let Some(value) = value.strip_prefix('>') {
+            (Bound::Excluded(parse(value)?.1), Bound::Unbounded)
+        } else if let Some(value) = value.strip_prefix("<=") {
+            (Bound::Unbounded, Bound::Included(parse(value)?.1))
+        } else if let Some(value) = value.strip_prefix('<') {
+            (Bound::Unbounded, Bound::Excluded(parse(value)?.0))
+        } else if let Some((from, to)) = value.split_once("..") {
+            if from.is_empty() && to.is_empty() {
+                return None;
+            }
+
+            let lower = if from.is_empty() {
+                Bound::Unbounded
+            } else {
+                Bound::Included(parse(from)?.0)
+            };
+
+            let upper = if to.is_empty() {
+                Bound::Unbounded
+            } else {
+                Bound::Included(parse(to)?.1)
+            };
+
+            (lower, upper)
+        } else {
+            let (lower, upper) = parse(value.strip_prefix('=').unwrap_or(value))?;
+            (Bound::Included(lower), Bound::Included(upper))
+        };
+
+        Some(Self { lower, upper })
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct QueryError {
+    pub message: String,
+    pub position: usize,
+}
+
+impl QueryError {
+    fn new(message: impl Into<String>, position: usize) -> Self {
+        Self {
+            message: message.into(),
+            position,
+        }
+    }
+}
+
+impl Display for QueryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl Error for QueryError {}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Token<'a> {
+    And,
+    End,
+    LeftParen,
+    Not,
+    Or,
+    RightParen,
+    Term(Cow<'a, str>),
+}
+
+impl Display for Token<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::And => f.write_str("'AND'"),
+            Token::End => f.write_str("end of query"),
+            Token::LeftParen => f.write_str("'('"),
+            Token::Not => f.write_str("'NOT'"),
+            Token::Or => f.write_str("'OR'"),
+            Token::RightParen => f.write_str("')'"),
+            Token::Term(term) => write!(f, "'{term}'"),
+        }
+    }
+}
+
+struct Parser<'a> {
+    tokens: &'a [(usize, Token<'a>)],
+    index: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn advance(&mut self) -> &'a (usize, Token<'a>) {
+        let token = self.peek();
+
+        if self.index < self.tokens.len() - 1 {
+            self.index += 1;
+        }
+
+        token
+    }
+
+    fn parse_and(&mut self, depth: usize) -> Result<QueryExpression<'a>, QueryError> {
+        let mut expressions = vec![self.parse_unary(depth)?];
+
+        loop {
+            match self.peek().1 {
+                Token::And => {
+                    self.advance();
+                    expressions.push(self.parse_unary(depth)?);
+                }
+                Token::LeftParen | Token::Not | Token::Term(_) => {
+                    expressions.push(self.parse_unary(depth)?);
+                }
+                _ => break,
+            }
+        }
+
+        Ok(if expressions.len() == 1 {
+            expressions.remove(0)
+        } else {
+            QueryExpression::And(expressions)
+        })
+    }
+
+    fn parse_or(&mut self, depth: usize) -> Result<QueryExpression<'a>, QueryError> {
+        let mut expressions = vec![self.parse_and(depth)?];
+
+        while self.peek().1 == Token::Or {
+            self.advance();
+            expressions.push(self.parse_and(depth)?);
+        }
+
+        Ok(if expressions.len() == 1 {
+            expressions.remove(0)
+        } else {
+            QueryExpression::Or(expressions)
+        })
+    }
+
+    fn parse_primary(&mut self, depth: usize) -> Result<QueryExpression<'a>, QueryError> {
+        let (position, token) = self.advance();
+
+        match token {
+            Token::LeftParen => {
+                if depth >= MAX_QUERY_DEPTH {
+                    return Err(QueryError::new("query nested too deeply", *position));
+                }
+
+                if self.peek().1 == Token::RightParen {
+                    return Err(QueryError::new("empty group", *position));
+                }
+
+                let expression = self.parse_or(depth + 1)?;
+
+                if self.advance().1 != Token::RightParen {
+                    return Err(QueryError::new("missing closing ')'", *position));
+                }
+
+                Ok(expression)
+            }
+            Token::Term(term) => QueryExpression::parse_term(term, *position),
+            token => Err(QueryError::new(format!("unexpected {token}"), *position)),
+        }
+    }
+
+    fn parse_unary(&mut self, depth: usize) -> Result<QueryExpression<'a>, QueryError> {
+        if self.peek().1 == Token::Not {
+            let (position, _) = self.advance();
+
+            if depth >= MAX_QUERY_DEPTH {
+                return Err(QueryError::new("query nested too deeply", *position));
+            }
+
+            Ok(QueryExpression::Not(Box::new(self.parse_unary(depth + 1)?)))
+        } else {
+            self.parse_primary(depth)
+        }
+    }
+
+    fn peek(&self) -> &'a (usize, Token<'a>) {
+        &self.tokens[self.index]
+    }
+}
+
+pub fn parse_query<'a>(
+    tokens: &'a [(usize, Token<'_>)],
+) -> Result<Option<QueryExpression<'a>>, QueryError> {
+    let mut parser = Parser { tokens, index: 0 };
+
+    if parser.peek().1 == Token::End {
+        return Ok(None);
+    }
+
+    let expression = parser.parse_or(0)?;
+    let (position, token) = parser.peek();
+
+    if *token == Token::End {
+        Ok(Some(expression))
+    } else {
+        Err(QueryError::new(format!("unexpected {token}"), *position))
+    }
+}
+
+pub fn tokenize_query(query: &str) -> Vec<(usize, Token<'_>)> {
+    let mut tokens = Vec::new();
+    let mut chars = query.char_indices().peekable();
+
+    let mut offset = (0, 0);
+
+    let mut position = |i: usize| {
+        offset = (i, offset.1 + query[offset.0..i].chars().count());
+        offset.1
+    };
+
+    while let Some((start, c)) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+
+        let token = match c {
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            '"' => Token::Term(read_quoted(&mut chars).into()),
+            '-' if chars
+                .peek()
+                .is_some_and(|(_, c)| !c.is_whitespace() && *c != ')') =>
+            {
+                Token::Not
+            }
+            _ => {
+                let mut end = start + c.len_utf8();
+                let mut depth = 0;
+
+                while let Some((i, c)) = chars.peek().copied() {
+                    if c == '"' || c.is_whitespace() || (c == ')' && depth == 0) {
+                        break;
+                    }
+
+                    if c == '(' {
+                        depth += 1;
+                    } else if c == ')' {
+                        depth -= 1;
+                    }
+
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+
+                match &query[start..end] {
+                    "AND" => Token::And,
+                    "NOT" => Token::Not,
+                    "OR" => Token::Or,
+                    term => Token::Term(term.into()),
+                }
+            }
+        };
+
+        tokens.push((position(start), token));
+    }
+
+    tokens.push((position(query.len()), Token::End));
+    tokens
+}
+
+pub fn wildcard_to_regex(wildcard: &str) -> String {
+    wildcard
+        .split_inclusive(['*', '?'])
+        .map(|part| {
+            if let Some(literal) = part.strip_suffix('*') {
+                format!("{}.*", escape(literal))
+            } else if let Some(literal) = part.strip_suffix('?') {
+                format!("{}.", escape(literal))
+            } else {
+                escape(part)
+            }
+        })
+        .collect()
+}
+
+fn read_quoted(chars: &mut Peekable<CharIndices<'_>>) -> String {
+    let mut term = String::new();
+
+    while let Some((_, c)) = chars.next() {
+        if c == '"' {
+            if let Some((_, '"')) = chars.peek() {
+                chars.next();
+            } else {
+                break;
+            }
+        }
+
+        term.push(c);
+    }
+
+    term
+}
+
+fn split_predicate(
+    value: &str,
+    position: usize,
+) -> Result<(&str, Option<Pattern<'_>>), QueryError> {
+    let mut depth = 0usize;
+    let mut quote = None;
+
+    for (i, c) in value.char_indices() {
+        match (c, quote) {
+            ('\'' | '"', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            (_, Some(_)) => {}
+            ('[' | '(', None) => depth += 1,
+            (']' | ')', None) => depth = depth.saturating_sub(1),
+            ('=', None) if depth == 0 => {
+                return Ok((&value[..i], Some(Pattern::Exact(&value[i + 1..]))));
+            }
+            ('~', None) if depth == 0 => {
+                let regex = &value[i + 1..];
+
+                Regex::new(regex)
+                    .map_err(|_| QueryError::new(format!("invalid regex '{regex}'"), position))?;
+
+                return Ok((&value[..i], Some(Pattern::Regex(regex))));
+            }
+            _ => {}
+        }
+    }
+
+    Ok((value, None))
+}
+
+fn parse_date(value: &str) -> Option<(String, String)> {
+    fn component(value: &str, range: std::ops::Range<usize>, max: u32) -> Option<u32> {
+        value
+            .get(range)
+            .filter(|digits| digits.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|digits| digits.parse().ok())
+            .filter(|component| *component <= max)
+    }
+
+    fn days_in_month(year: u32, month: u32) -> u32 {
+        match month {
+            2 if year.is_multiple_of(4)
+                && (!year.is_multiple_of(100) || year.is_multiple_of(400)) =>
+            {
+                29
+            }
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+
+    let value = value.replacen('T', " ", 1);
+
+    if value.len() < 10 || value.as_bytes()[4] != b'-' || value.as_bytes()[7] != b'-' {
+        return None;
+    }
+
+    let year = component(&value, 0..4, 9999)?;
+    let month = component(&value, 5..7, 12).filter(|month| *month > 0)?;
+
+    component(&value, 8..10, days_in_month(year, month)).filter(|day| *day > 0)?;
+
+    match value.len() {
+        10 => Some((value.clone(), format!("{value} 23:59:59"))),
+        16 if value.as_bytes()[10] == b' '
+            && value.as_bytes()[13] == b':'
+            && component(&value, 11..13, 23).is_some()
+            && component(&value, 14..16, 59).is_some() =>
+        {
+            Some((format!("{value}:00"), format!("{value}:59")))
+        }
+        19 if value.as_bytes()[16] == b':' && component(&value, 17..19, 59).is_some() => {
+            parse_date(&value[..16]).map(|_| (value.clone(), value))
+        }
+        _ => None,
+    }
+}
+
+fn parse_number(value: &str) -> Option<(i64, i64)> {
+    value.parse().ok().map(|number| (number, number))
+}
+
+fn parse_size(value: &str) -> Option<(i64, i64)> {
+    let value = value.to_ascii_lowercase();
+    let digits = value.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+
+    let multiplier = match &value[digits.len()..] {
+        "" | "b" => 1,
+        "k" | "kb" => 1 << 10,
+        "m" | "mb" => 1 << 20,
+        "g" | "gb" => 1 << 30,
+        _ => return None,
+    };
+
+    digits
+        .parse::<i64>()
+        .ok()
+        .and_then(|size| size.checked_mul(multiplier))
+        .map(|size| (size, size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn parse(query: &str) -> Result<Option<String>, QueryError> {
+        let tokens = tokenize_query(query);
+        parse_query(&tokens).map(|expression| expression.map(|e| format!("{e:?}")))
+    }
+
+    #[test]
+    fn test_query_expressions() {
+        let tokens = tokenize_query(r#"event-id:123 id:123 regex:abc abc:def abc "abc def""#);
+
+        let mut iter = tokens.iter().filter_map(|(position, token)| match token {
+            Token::Term(term) => Some(QueryExpression::parse_term(term, *position)),
+            _ => None,
+        });
+
+        let range = |value| Range {
+            lower: Bound::Included(value),
+            upper: Bound::Included(value),
+        };
+
+        assert_eq!(iter.next(), Some(Ok(QueryExpression::EventId(range(123)))));
+        assert_eq!(iter.next(), Some(Ok(QueryExpression::Id(range(123)))));
+        assert_eq!(iter.next(), Some(Ok(QueryExpression::Regex("abc"))));
+        assert_eq!(
+            iter.next(),
+            Some(Ok(QueryExpression::Header(
+                "abc",
+                Some(Pattern::Contains("def"))
+            )))
+        );
+        assert_eq!(iter.next(), Some(Ok(QueryExpression::Text("abc"))));
+        assert_eq!(iter.next(), Some(Ok(QueryExpression::Text("abc def"))));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[rstest]
+    #[case("5", Some((Bound::Included(5), Bound::Included(5))))]
+    #[case("=5", Some((Bound::Included(5), Bound::Included(5))))]
+    #[case(">5", Some((Bound::Excluded(5), Bound::Unbounded)))]
+    #[case(">=5", Some((Bound::Included(5), Bound::Unbounded)))]
+    #[case("<5", Some((Bound::Unbounded, Bound::Excluded(5))))]
+    #[case("<=5", Some((Bound::Unbounded, Bound::Included(5))))]
+    #[case("5..10", Some((Bound::Included(5), Bound::Included(10))))]
+    #[case("5..", Some((Bound::Included(5), Bound::Unbounded)))]
+    #[case("..10", Some((Bound::Unbounded, Bound::Included(10))))]
+    #[case("..", None)]
+    #[case("", None)]
+    #[case(">", None)]
+    #[case("abc", None)]
+    #[case("5..abc", None)]
+    fn test_range_parse(
+        #[case] value: &str,
+        #[case] expected_bounds: Option<(Bound<i64>, Bound<i64>)>,
+    ) {
+        let range = Range::parse(value, parse_number).map(|range| (range.lower, range.upper));
+        assert_eq!(range, expected_bounds, "value = {value}");
+    }
+
+    #[rstest]
+    #[case("2025-01-31", Some(("2025-01-31", "2025-01-31 23:59:59")))]
+    #[case("2025-01-31T10:20", Some(("2025-01-31 10:20:00", "2025-01-31 10:20:59")))]
+    #[case("2025-01-31 10:20:30", Some(("2025-01-31 10:20:30", "2025-01-31 10:20:30")))]
+    #[case("2025-13-01", None)]
+    #[case("2025-01-00", None)]
+    #[case("2025-01-01T24:00", None)]
+    #[case("2025-01-01T10:60", None)]
+    #[case("2025-01-01T10:00:60", None)]
+    #[case("2025/01/01", None)]
+    #[case("2025-1-1", None)]
+    #[case("yesterday", None)]
+    fn test_parse_date(#[case] value: &str, #[case] expected_date: Option<(&str, &str)>) {
+        let date = parse_date(value);
+
+        assert_eq!(
+            date.as_ref().map(|(from, to)| (from.as_str(), to.as_str())),
+            expected_date,
+            "value = {value}"
+        );
+    }
+
+    #[rstest]
+    #[case("100", Some(100))]
+    #[case("100b", Some(100))]
+    #[case("2k", Some(2048))]
+    #[case("2KB", Some(2048))]
+    #[case("1mb", Some(1_048_576))]
+    #[case("1g", Some(1_073_741_824))]
+    #[case("1tb", None)]
+    #[case("mb", None)]
+    #[case("1.5mb", None)]
+    fn test_parse_size(#[case] value: &str, #[case] expected_size: Option<i64>) {
+        assert_eq!(
+            parse_size(value).map(|(size, _)| size),
+            expected_size,
+            "value = {value}"
+        );
+    }
+
+    #[rstest]
+    #[case("", None)]
+    #[case("a", Some(r#"Text("a")"#))]
+    #[case("a b", Some(r#"And([Text("a"), Text("b")])"#))]
+    #[case("a AND b", Some(r#"And([Text("a"), Text("b")])"#))]
+    #[case("a OR b", Some(r#"Or([Text("a"), Text("b")])"#))]
+    #[case("a b OR c", Some(r#"Or([And([Text("a"), Text("b")]), Text("c")])"#))]
+    #[case("a (b OR c)", Some(r#"And([Text("a"), Or([Text("b"), Text("c")])])"#))]
+    #[case("NOT a", Some(r#"Not(Text("a"))"#))]
+    #[case("-a", Some(r#"Not(Text("a"))"#))]
+    #[case(r#"-"a b""#, Some(r#"Not(Text("a b"))"#))]
+    #[case("-(a OR b)", Some(r#"Not(Or([Text("a"), Text("b")]))"#))]
+    #[case("- a", Some(r#"And([Text("-"), Text("a")])"#))]
+    #[case("a-b", Some(r#"Text("a-b")"#))]
+    #[case(r#""OR""#, Some(r#"Text("OR")"#))]
+    #[case("or", Some(r#"Text("or")"#))]
+    #[case("regex:(a|b)", Some(r#"Regex("(a|b)")"#))]
+    #[case("(regex:(a|b))", Some(r#"Regex("(a|b)")"#))]
+    #[case(
+        "(system-a OR system-b) -heartbeat event-id:123",
+        Some(
+            r#"And([Or([Text("system-a"), Text("system-b")]), Not(Text("heartbeat")), EventId(Range { lower: Included(123), upper: Included(123) })])"#
+        )
+    )]
+    #[case("system:abc", Some(r#"System(Exact("abc"))"#))]
+    #[case("tag:bad-payload", Some(r#"Tag(Exact("bad-payload"))"#))]
+    #[case("type:event_*", Some(r#"Type(Wildcard("event_*"))"#))]
+    #[case("user-agent:curl/?.*", Some(r#"UserAgent(Wildcard("curl/?.*"))"#))]
+    #[case(
+        "entity-event-id:1..",
+        Some(r#"EntityEventId(Range { lower: Included(1), upper: Unbounded })"#)
+    )]
+    #[case("header:mgs-system-id", Some(r#"Header("mgs-system-id", None)"#))]
+    #[case(
+        "header:content-type=*json*",
+        Some(r#"Header("content-type", Some(Wildcard("*json*")))"#)
+    )]
+    #[case(
+        "header:system=system:a",
+        Some(r#"Header("system", Some(Exact("system:a")))"#)
+    )]
+    #[case(
+        "header:authorization~^Bearer",
+        Some(r#"Header("authorization", Some(Regex("^Bearer")))"#)
+    )]
+    #[case("abc:def", Some(r#"Header("abc", Some(Contains("def")))"#))]
+    #[case("event:VACANCY/*", Some(r#"Event(Wildcard("VACANCY/*"))"#))]
+    #[case("event:VACANCY_CREATED", Some(r#"Event(Exact("VACANCY_CREATED"))"#))]
+    #[case("field:vacancyId", Some(r#"Field("vacancyId", None)"#))]
+    #[case(
+        "field:vacancyId=123",
+        Some(r#"Field("vacancyId", Some(Exact("123")))"#)
+    )]
+    #[case(
+        "field:vacancyId=12*",
+        Some(r#"Field("vacancyId", Some(Wildcard("12*")))"#)
+    )]
+    #[case("field:personId~^9", Some(r#"Field("personId", Some(Regex("^9")))"#))]
+    #[case("json:$.entity", Some(r#"Json("$.entity", None)"#))]
+    #[case(
+        "json:$.entity.vacancyId=42",
+        Some(r#"Json("$.entity.vacancyId", Some(Exact("42")))"#)
+    )]
+    #[case(
+        "json:$.eventDesc~Hired",
+        Some(r#"Json("$.eventDesc", Some(Regex("Hired")))"#)
+    )]
+    #[case(
+        "json:$.items[?@.name=='a=b'].id=1",
+        Some(r#"Json("$.items[?@.name=='a=b'].id", Some(Exact("1")))"#)
+    )]
+    #[case(
+        "xpath://Folder[@type='CL']/@id=123",
+        Some(r#"XPath("//Folder[@type='CL']/@id", Some(Exact("123")))"#)
+    )]
+    #[case(
+        "xpath:count(//vacancyId)>1",
+        Some(r#"XPath("count(//vacancyId)>1", None)"#)
+    )]
+    #[case(
+        "id:100..200",
+        Some(r#"Id(Range { lower: Included(100), upper: Included(200) })"#)
+    )]
+    #[case(
+        "event-id:>5000",
+        Some(r#"EventId(Range { lower: Excluded(5000), upper: Unbounded })"#)
+    )]
+    #[case(
+        "size:>1mb",
+        Some(r#"Size(Range { lower: Excluded(1048576), upper: Unbounded })"#)
+    )]
+    #[case(
+        "date:2025-01-01..2025-01-31",
+        Some(
+            r#"Date(Range { lower: Included("2025-01-01"), upper: Included("2025-01-31 23:59:59") })"#
+        )
+    )]
+    #[case(
+        "date:2024-02-29",
+        Some(
+            r#"Date(Range { lower: Included("2024-02-29"), upper: Included("2024-02-29 23:59:59") })"#
+        )
+    )]
+    fn test_parse_query(#[case] query: &str, #[case] expected_expression: Option<&str>) {
+        assert_eq!(
+            parse(query),
+            Ok(expected_expression.map(Into::into)),
+            "query = {query}"
+        );
+    }
+
+    #[rstest]
+    #[case("(", "unexpected end of query at position 1")]
+    #[case("(a", "missing closing ')' at position 0")]
+    #[case("a )", "unexpected ')' at position 2")]
+    #[case("()", "empty group at position 0")]
+    #[case("a OR", "unexpected end of query at position 4")]
+    #[case("OR a", "unexpected 'OR' at position 0")]
+    #[case("a AND OR b", "unexpected 'OR' at position 6")]
+    #[case("NOT", "unexpected end of query at position 3")]
+    #[case("ä (", "unexpected end of query at position 3")]
+    #[case("a id:abc", "invalid number 'abc' at position 2")]
+    #[case("entity-event-id:x", "invalid number 'x' at position 0")]
+    #[case("header:=value", "missing header name at position 0")]
+    #[case("field:=1", "missing field name at position 0")]
+    #[case("json:$.a~(", "invalid regex '(' at position 0")]
+    #[case("header:a~(", "invalid regex '(' at position 0")]
+    #[case("a regex:(", "invalid regex '(' at position 2")]
+    #[case("xpath:=1", "missing XPath at position 0")]
+    #[case("event-id:>x", "invalid number '>x' at position 0")]
+    #[case("size:1tb", "invalid size '1tb' at position 0")]
+    #[case("date:2025-02-30..x", "invalid date '2025-02-30..x' at position 0")]
+    #[case("date:2025-02-29", "invalid date '2025-02-29' at position 0")]
+    #[case("date:2025-04-31", "invalid date '2025-04-31' at position 0")]
+    #[case("date:1900-02-29", "invalid date '1900-02-29' at position 0")]
+    #[case(
+        "date:2025-01-01..2025-06-31",
+        "invalid date '2025-01-01..2025-06-31' at position 0"
+    )]
+    fn test_parse_query_error(#[case] query: &str, #[case] expected_error: &str) {
+        assert_eq!(
+            parse(query).map_err(|e| e.to_string()),
+            Err(expected_error.into()),
+            "query = {query}"
+        );
+    }
+
+    #[rstest]
+    #[case("-", "", 64, 64)]
+    #[case("NOT ", "", 64, 256)]
+    #[case("(", ")", 64, 64)]
+    #[case("-(", ")", 32, 64)]
+    fn test_parse_query_depth(
+        #[case] open: &str,
+        #[case] close: &str,
+        #[case] max_levels: usize,
+        #[case] expected_position: usize,
+    ) {
+        let nested = |levels| format!("{}a{}", open.repeat(levels), close.repeat(levels));
+
+        assert!(parse(&nested(max_levels)).is_ok());
+
+        for levels in [max_levels + 1, 200_000] {
+            assert_eq!(
+                parse(&nested(levels)).map_err(|e| e.to_string()),
+                Err(format!(
+                    "query nested too deeply at position {expected_position}"
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_query_invalid_json_path() {
+        let error = parse("a json:$[").unwrap_err();
+
+        assert!(error.message.starts_with("invalid JSONPath '$[': "));
+        assert_eq!(error.position, 2);
+    }
+
+    #[test]
+    fn test_parse_query_invalid_xpath() {
+        let error = parse("xpath://a[").unwrap_err();
+
+        assert!(error.message.starts_with("invalid XPath '//a[': "));
+        assert_eq!(error.position, 0);
+    }
+
+    #[rstest]
+    #[case("", &[])]
+    #[case(" ", &[])]
+    #[case("a", &["a"])]
+    #[case(" a ", &["a"])]
+    #[case("a b", &["a", "b"])]
+    #[case(" a b ", &["a", "b"])]
+    #[case("abc", &["abc"])]
+    #[case(" abc ", &["abc"])]
+    #[case("abc def", &["abc", "def"])]
+    #[case(" abc def ", &["abc", "def"])]
+    #[case(r#""""#, &[""])]
+    #[case(r#""a""#, &["a"])]
+    #[case(r#"""#, &[""])]
+    #[case(r#""a"#, &["a"])]
+    #[case(r#"" a ""#, &[" a "])]
+    #[case(r#""abc""#, &["abc"])]
+    #[case(r#"" abc def ""#, &[" abc def "])]
+    #[case(r#""a" "b""#, &["a", "b"])]
+    #[case(r#"a"b"c"#, &["a", "b", "c"])]
+    #[case(r#""a"b"#, &["a", "b"])]
+    #[case(r#"""a"b"#, &["", "a", "b"])]
+    #[case(r#""""a"b"#, &[r#""a"#, "b"])]
+    #[case(r#"""""a"b"#, &[r#"""#, "a", "b"])]
+    #[case(r#""""""a"b"#, &[r#"""a"#, "b"])]
+    #[case(r#""""""#, &[r#"""#])]
+    #[case(r#""regex:abc "" def""#, &[r#"regex:abc " def"#])]
+    fn test_tokenize_query(#[case] query: &str, #[case] expected_terms: &[&str]) {
+        let tokens = tokenize_query(query);
+
+        let terms: Vec<_> = tokens
+            .iter()
+            .filter_map(|(_, token)| match token {
+                Token::Term(term) => Some(term.as_ref()),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(terms, expected_terms, "query = {query}");
+    }
+
+    #[test]
+    fn test_tokenize_query_operators() {
+        assert_eq!(
+            tokenize_query(r#"(a OR "b") AND -c NOT d"#),
+            vec![
+                (0, Token::LeftParen),
+                (1, Token::Term("a".into())),
+                (3, Token::Or),
+                (6, Token::Term("b".into())),
+                (9, Token::RightParen),
+                (11, Token::And),
+                (15, Token::Not),
+                (16, Token::Term("c".into())),
+                (18, Token::Not),
+                (22, Token::Term("d".into())),
+                (23, Token::End),
+            ]
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock, Weak},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::Result;
+use tracing::{error, info};
+
+pub struct Reloadable<T>(Arc<RwLock<Arc<T>>>);
+
+impl<T> Reloadable<T> {
+    pub fn new(value: T) -> Self {
+        Self(Arc::new(RwLock::new(Arc::new(value))))
+    }
+
+    pub fn get(&self) -> Arc<T> {
+        self.0
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    pub fn set(&self, value: T) {
+        *self
+            .0
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(value);
+    }
+}
+
+impl<T> Reloadable<T>
+where
+    T: Send + Sync + 'static,
+{
+    pub fn load(path: PathBuf, parse: fn(&str) -> Result<T>) -> Result<Self> {
+        Self::load_with(path, parse, |_| {})
+    }
+
+    pub fn load_with(
+        path: PathBuf,
+        parse: fn(&str) -> Result<T>,
+        on_reload: impl Fn(Arc<T>) + Send + 'static,
+    ) -> Result<Self> {
+        let version = file_version(&path);
+        let reloadable = Self::new(parse(&fs::read_to_string(&path)?)?);
+
+        reloadable.watch(path, version, parse, Duration::from_secs(1), on_reload);
+
+        Ok(reloadable)
+    }
+
+    fn watch(
+        &self,
+        path: PathBuf,
+        mut version: Option<(SystemTime, u64)>,
+        parse: fn(&str) -> Result<T>,
+        interval: Duration,
+        on_reload: impl Fn(Arc<T>) + Send + 'static,
+    ) {
+        let value: Weak<RwLock<Arc<T>>> = Arc::downgrade(&self.0);
+
+        thread::spawn(move || {
+            loop {
+                thread::sleep(interval);
+
+                let Some(value) = value.upgrade() else {
+                    break;
+                };
+
+                let current = file_version(&path);
+
+                if current == version {
+                    continue;
+                }
+
+                version = current;
+
+                match fs::read_to_string(&path)
+                    .map_err(Into::into)
+                    .and_then(|text| parse(&text))
+                {
+                    Ok(parsed) => {
+                        let reloadable = Self(value);
+
+                        reloadable.set(parsed);
+                        info!(?path, "reloaded");
+
+                        on_reload(reloadable.get());
+                    }
+                    Err(e) => error!(?path, "reload failed, keeping previous version: {e:#}"),
+                }
+            }
+        });
+    }
+}
+
+impl<T> Clone for Reloadable<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+fn file_version(path: &Path) -> Option<(SystemTime, u64)> {
+    fs::metadata(path)
+        .and_then(|metadata| Ok((metadata.modified()?, metadata.len())))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn parse(text: &str) -> Result<i32> {
+        Ok(text.trim().parse()?)
+    }
+
+    #[test]
+    fn test_reloadable_watch() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("sink-reload-{}", std::process::id()));
+        fs::write(&path, "1")?;
+
+        let reloaded = Arc::new(Mutex::new(Vec::new()));
+        let reloadable = Reloadable::new(parse("1")?);
+
+        reloadable.watch(path.clone(), None, parse, Duration::from_millis(10), {
+            let reloaded = reloaded.clone();
+            move |value: Arc<i32>| reloaded.lock().unwrap().push(*value)
+        });
+
+        let wait_for = |expected| {
+            for _ in 0..200 {
+                if reloaded.lock().unwrap().last() == Some(&expected) {
+                    return true;
+                }
+
+                thread::sleep(Duration::from_millis(10));
+            }
+
+            false
+        };
+
+        fs::write(&path, "2")?;
+        assert!(wait_for(2));
+        assert_eq!(*reloadable.get(), 2);
+
+        fs::write(&path, "invalid")?;
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(*reloadable.get(), 2);
+
+        fs::write(&path, "3")?;
+        assert!(wait_for(3));
+
+        fs::remove_file(&path)?;
+
+        Ok(())
+    }
+}
@@ -1,18 +1,37 @@
-use std::{borrow::Cow, future::Future, mem::take, path::Path};
-
-use crate::model::{Item, ItemFilter, ItemHeader, ItemSummary, NewItem};
+use std::{future::Future, ops::Bound, path::Path};
+
+use crate::{
+    cursor::{Cursor, CursorError, CursorValue},
+    model::{
+        CountMode, EventTypeGroup, FacetValue, HistogramBucket, HistogramCount, HistogramSplit,
+        Item, ItemClassification, ItemField, ItemFilter, ItemHeader, ItemNote, ItemPage,
+        ItemSummary, ItemValidationError, NewItem, NewItemNote, NewSavedSearch, SavedSearch,
+        SortField, SortKey, Stats, TimelineStep,
+    },
+    query::{Pattern, QueryExpression, Range, parse_query, tokenize_query, wildcard_to_regex},
+    xml,
+};
 
-use anyhow::Result;
-use regex::bytes::Regex;
-use rusqlite::{Connection, functions::FunctionFlags};
+use anyhow::{Error, Result, anyhow};
+use regex::{bytes::Regex, escape};
+use rusqlite::{Connection, functions::FunctionFlags, types::ValueRef};
+use serde_json::Value;
+use serde_json_path::JsonPath;
+use sxd_xpath::{Context, Value as XPathValue};
 
 use sqlx::{
-    Database, Encode, QueryBuilder, Sqlite, SqliteConnection, SqlitePool, Type, migrate,
+    Database, Encode, QueryBuilder, Row, Sqlite, SqliteConnection, SqlitePool, Type, migrate,
     prelude::FromRow,
     query, query_as, query_scalar,
-    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions},
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteRow},
+    types::Json,
 };
 
+const ESTIMATED_COUNT_LIMIT: i32 = 10_000;
+// Related items and timelines are keyed on event_id (the mgs-event-id header),
+// which identifies a single event; entity_event_id is its event type.
+const EVENT_ITEMS_LIMIT: i64 = 100;
+
 trait QueryBuilderExt<'a, DB: Database> {
     fn append_if_is_some<T>(&mut self, sql: &str, value: Option<T>) -> &mut Self
     where
@@ -53,48 +72,502 @@ impl<'a, DB: Database> QueryBuilderExt<'a, DB> for QueryBuilder<'a, DB> {
     }
 }
 
-#[derive(Debug, Eq, PartialEq)]
-enum QueryExpression<'a> {
-    EventId(&'a str),
-    Header(&'a str, &'a str),
-    Id(&'a str),
-    Regex(&'a str),
-    Text(&'a str),
+trait QueryExpressionExt<'a> {
+    fn push_query_expression(
+        &mut self,
+        expression: &QueryExpression<'a>,
+        ignore_case: bool,
+    ) -> &mut Self;
+
+    fn push_cursor_value(&mut self, value: &CursorValue) -> &mut Self;
+
+    fn push_item_filter(
+        &mut self,
+        filter: &'a ItemFilter,
+        expression: Option<&QueryExpression<'a>>,
+    ) -> &mut Self;
+
+    fn push_keyset(
+        &mut self,
+        sort: &[SortField],
+        push_value: impl Fn(&mut Self, usize),
+    ) -> &mut Self;
+
+    fn push_content_type(&mut self, content_type: &str) -> &mut Self;
+    fn push_pattern(&mut self, column: &str, pattern: &Pattern<'a>, ignore_case: bool)
+    -> &mut Self;
+
+    fn push_range<T>(&mut self, column: &str, range: &Range<T>) -> &mut Self
+    where
+        T: 'a + Clone + Encode<'a, Sqlite> + PartialEq + Type<Sqlite>;
 }
 
-impl<'a> From<&'a str> for QueryExpression<'a> {
-    fn from(value: &'a str) -> Self {
-        if let Some((name, value)) = value.split_once(':') {
-            match name {
-                "body" => QueryExpression::Text(value),
-                "event-id" => QueryExpression::EventId(value),
-                "id" => QueryExpression::Id(value),
-                "regex" => QueryExpression::Regex(value),
-                _ => QueryExpression::Header(name, value),
+impl<'a> QueryExpressionExt<'a> for QueryBuilder<'a, Sqlite> {
+    fn push_query_expression(
+        &mut self,
+        expression: &QueryExpression<'a>,
+        ignore_case: bool,
+    ) -> &mut Self {
+        match expression {
+            QueryExpression::And(expressions) | QueryExpression::Or(expressions) => {
+                let operator = if matches!(expression, QueryExpression::And(_)) {
+                    " AND "
+                } else {
+                    " OR "
+                };
+
+                self.push('(');
+
+                for (i, expression) in expressions.iter().enumerate() {
+                    if i > 0 {
+                        self.push(operator);
+                    }
+
+                    self.push_query_expression(expression, ignore_case);
+                }
+
+                self.push(')')
             }
-        } else {
-            QueryExpression::Text(value)
+            QueryExpression::Date(date) => self.push_range("submit_date", date),
+            QueryExpression::EntityEventId(entity_event_id) => {
+                self.push_range("entity_event_id", entity_event_id)
+            }
+            QueryExpression::Event(pattern) => self
+                .push(
+                    "EXISTS (SELECT 1 FROM event_type WHERE event_type.id = entity_event_id AND (",
+                )
+                .push_pattern("event_group || '/' || name", pattern, ignore_case)
+                .push(" OR ")
+                .push_pattern("event_group", pattern, ignore_case)
+                .push(" OR ")
+                .push_pattern("name", pattern, ignore_case)
+                .push("))"),
+            QueryExpression::EventId(event_id) => self.push_range("event_id", event_id),
+            QueryExpression::Field(name, pattern) => {
+                self.push("EXISTS (SELECT 1 FROM item_field WHERE item_id = id AND name = ")
+                    .push_bind(*name);
+
+                if let Some(pattern) = pattern {
+                    self.push(" AND ")
+                        .push_pattern("value", pattern, ignore_case);
+                }
+
+                self.push(')')
+            }
+            QueryExpression::Header(name, pattern) => {
+                self.push("EXISTS (SELECT 1 FROM item_header WHERE item_id = id AND name = lower(")
+                    .push_bind(*name)
+                    .push(')');
+
+                if let Some(pattern) = pattern {
+                    self.push(" AND ")
+                        .push_pattern("CAST(value AS TEXT)", pattern, ignore_case);
+                }
+
+                self.push(')')
+            }
+            QueryExpression::Id(id) => self.push_range("id", id),
+            QueryExpression::Json(path, pattern) => self
+                .push("EXISTS (SELECT 1 FROM item_body WHERE item_id = id AND ")
+                .push_content_type("json")
+                .push(" AND json_matches(")
+                .push_bind(*path)
+                .push(", ")
+                .push_bind(value_pattern(pattern.as_ref()))
+                .push(", body))"),
+            QueryExpression::XPath(path, pattern) => self
+                .push("EXISTS (SELECT 1 FROM item_body WHERE item_id = id AND ")
+                .push_content_type("xml")
+                .push(" AND xpath_matches(")
+                .push_bind(*path)
+                .push(", ")
+                .push_bind(value_pattern(pattern.as_ref()))
+                .push(", body))"),
+            QueryExpression::Not(expression) => self
+                .push('(')
+                .push_query_expression(expression, ignore_case)
+                .push(") IS NOT 1"),
+            QueryExpression::Regex(regex) => self
+                .push("EXISTS (SELECT 1 FROM item_body WHERE item_id = id AND matches(")
+                .push_bind(*regex)
+                .push(", body))"),
+            QueryExpression::Size(size) => self
+                .push("EXISTS (SELECT 1 FROM item_body WHERE item_id = id AND ")
+                .push_range("length(body)", size)
+                .push(')'),
+            QueryExpression::System(pattern) => self.push_pattern("system", pattern, ignore_case),
+            QueryExpression::Tag(pattern) => self
+                .push("EXISTS (SELECT 1 FROM item_tag WHERE item_id = id AND ")
+                .push_pattern("tag", pattern, ignore_case)
+                .push(')'),
+            QueryExpression::Text(text) => self
+                .push("EXISTS (SELECT 1 FROM item_body WHERE item_id = id AND ")
+                .push_pattern("body", &Pattern::Contains(text), ignore_case)
+                .push(')'),
+            QueryExpression::Type(pattern) => self.push_pattern("type", pattern, ignore_case),
+            QueryExpression::UserAgent(pattern) => {
+                self.push_pattern("user_agent", pattern, ignore_case)
+            }
+        }
+    }
+
+    fn push_cursor_value(&mut self, value: &CursorValue) -> &mut Self {
+        match value {
+            CursorValue::Integer(value) => self.push_bind(*value),
+            CursorValue::Text(value) => self.push_bind(value.clone()),
+        }
+    }
+
+    fn push_item_filter(
+        &mut self,
+        filter: &'a ItemFilter,
+        expression: Option<&QueryExpression<'a>>,
+    ) -> &mut Self {
+        if let Some(expression) = expression {
+            self.push(" AND ")
+                .push_query_expression(expression, filter.ignore_case.unwrap_or_default());
+        }
+
+        if let Some(systems) = &filter.system {
+            self.push(" AND system")
+                .append_in(systems.comma_separated());
+        }
+
+        if let Some(types) = &filter.r#type {
+            self.push(" AND type").append_in(types.comma_separated());
+        }
+
+        if let Some(event_types) = &filter.event_type {
+            self.push(
+                " AND (type NOT IN ('event_notification', 'event_payload') OR entity_event_id",
+            )
+            .append_in(event_types.comma_separated())
+            .push(" OR entity_event_id IN (SELECT id FROM event_type WHERE event_group")
+            .append_in(event_types.comma_separated())
+            .push(" OR name")
+            .append_in(event_types.comma_separated())
+            .push("))");
+        }
+
+        self.append_if_is_some(" AND submit_date >= ", filter.from.as_ref())
+            .append_if_is_some(" AND submit_date <= ", filter.to.as_ref())
+            .append_if_is_some(" AND pinned = ", filter.pinned)
+            .append_if_is_some(" AND valid = ", filter.valid)
+    }
+
+    fn push_keyset(
+        &mut self,
+        sort: &[SortField],
+        push_value: impl Fn(&mut Self, usize),
+    ) -> &mut Self {
+        self.push('(');
+
+        for (i, field) in sort.iter().enumerate() {
+            if i > 0 {
+                self.push(" OR ");
+            }
+
+            self.push('(');
+
+            for (j, previous) in sort[..i].iter().enumerate() {
+                self.push(sort_expression(previous.key)).push(" = ");
+                push_value(self, j);
+                self.push(" AND ");
+            }
+
+            self.push(sort_expression(field.key))
+                .push(if field.descending { " < " } else { " > " });
+
+            push_value(self, i);
+            self.push(')');
+        }
+
+        self.push(')')
+    }
+
+    fn push_content_type(&mut self, content_type: &str) -> &mut Self {
+        self.push("EXISTS (SELECT 1 FROM item_header WHERE item_id = id AND name = 'content-type' AND CAST(value AS TEXT) LIKE '%")
+            .push(content_type)
+            .push("%')")
+    }
+
+    fn push_pattern(
+        &mut self,
+        column: &str,
+        pattern: &Pattern<'a>,
+        ignore_case: bool,
+    ) -> &mut Self {
+        if ignore_case {
+            let regex = match pattern {
+                Pattern::Contains(value) => format!("(?i){}", escape(value)),
+                Pattern::Exact(value) => format!("(?i)\\A{}\\z", escape(value)),
+                Pattern::Regex(regex) => format!("(?i){regex}"),
+                Pattern::Wildcard(value) => format!("(?is)\\A{}\\z", wildcard_to_regex(value)),
+            };
+
+            return self
+                .push("matches(")
+                .push_bind(regex)
+                .push(", ")
+                .push(column)
+                .push(')');
+        }
+
+        match pattern {
+            Pattern::Contains(value) => self
+                .push("instr(")
+                .push(column)
+                .push(", ")
+                .push_bind(*value)
+                .push(") > 0"),
+            Pattern::Exact(value) => self.push(column).push(" = ").push_bind(*value),
+            Pattern::Regex(regex) => self
+                .push("matches(")
+                .push_bind(*regex)
+                .push(", ")
+                .push(column)
+                .push(')'),
+            Pattern::Wildcard(value) => self
+                .push(column)
+                .push(" GLOB ")
+                .push_bind(value.replace('[', "[[]")),
         }
     }
+
+    fn push_range<T>(&mut self, column: &str, range: &Range<T>) -> &mut Self
+    where
+        T: 'a + Clone + Encode<'a, Sqlite> + PartialEq + Type<Sqlite>,
+    {
+        if let (Bound::Included(lower), Bound::Included(upper)) = (&range.lower, &range.upper)
+            && lower == upper
+        {
+            return self.push(column).push(" = ").push_bind(lower.clone());
+        }
+
+        self.push("(1 = 1");
+
+        match &range.lower {
+            Bound::Included(lower) => self
+                .push(" AND ")
+                .push(column)
+                .push(" >= ")
+                .push_bind(lower.clone()),
+            Bound::Excluded(lower) => self
+                .push(" AND ")
+                .push(column)
+                .push(" > ")
+                .push_bind(lower.clone()),
+            Bound::Unbounded => self,
+        };
+
+        match &range.upper {
+            Bound::Included(upper) => self
+                .push(" AND ")
+                .push(column)
+                .push(" <= ")
+                .push_bind(upper.clone()),
+            Bound::Excluded(upper) => self
+                .push(" AND ")
+                .push(column)
+                .push(" < ")
+                .push_bind(upper.clone()),
+            Bound::Unbounded => self,
+        };
+
+        self.push(')')
+    }
 }
 
-pub trait Repository: Clone + Send + Sync {
-    fn get_item(&self, id: i64) -> impl Future<Output = Result<Option<Item>>> + Send;
+pub trait Repository: Clone + Send + Sync + 'static {
+    fn add_item_note(
+        &self,
+        item_id: i64,
+        note: &NewItemNote,
+    ) -> impl Future<Output = Result<Option<ItemNote>>> + Send;
 
-    fn get_items(
+    fn add_item_tag(&self, item_id: i64, tag: &str) -> impl Future<Output = Result<bool>> + Send;
+    fn delete_saved_search(&self, name: &str) -> impl Future<Output = Result<bool>> + Send;
+
+    fn get_facets(
         &self,
         filter: &ItemFilter,
-    ) -> impl Future<Output = Result<(Vec<ItemSummary>, i32)>> + Send;
+    ) -> impl Future<Output = Result<Vec<FacetValue>>> + Send;
 
+    fn get_histogram(
+        &self,
+        filter: &ItemFilter,
+        bucket: HistogramBucket,
+        split: Option<HistogramSplit>,
+    ) -> impl Future<Output = Result<Vec<HistogramCount>>> + Send;
+
+    fn get_item(&self, id: i64) -> impl Future<Output = Result<Option<Item>>> + Send;
+
+    fn get_items(&self, filter: &ItemFilter) -> impl Future<Output = Result<ItemPage>> + Send;
+
+    fn get_related_items(&self, id: i64) -> impl Future<Output = Result<Vec<ItemSummary>>> + Send;
+
+    fn get_saved_search(
+        &self,
+        name: &str,
+    ) -> impl Future<Output = Result<Option<SavedSearch>>> + Send;
+
+    fn get_saved_searches(&self) -> impl Future<Output = Result<Vec<SavedSearch>>> + Send;
+    fn get_stats(&self) -> impl Future<Output = Result<Stats>> + Send;
     fn get_systems(&self) -> impl Future<Output = Result<Vec<String>>> + Send;
+
+    fn get_timeline(&self, event_id: i64)
+    -> impl Future<Output = Result<Vec<TimelineStep>>> + Send;
+
     fn insert_item(&self, item: &NewItem<'_>) -> impl Future<Output = Result<i64>> + Send;
+
+    fn remove_item_note(
+        &self,
+        item_id: i64,
+        note_id: i64,
+    ) -> impl Future<Output = Result<bool>> + Send;
+
+    fn remove_item_tag(&self, item_id: i64, tag: &str)
+    -> impl Future<Output = Result<bool>> + Send;
+
+    fn save_saved_search(
+        &self,
+        name: &str,
+        search: &NewSavedSearch,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    fn set_event_types(&self, groups: &[EventTypeGroup])
+    -> impl Future<Output = Result<()>> + Send;
+
+    fn set_item_classification(
+        &self,
+        id: i64,
+        classification: &ItemClassification,
+    ) -> impl Future<Output = Result<bool>> + Send;
+
+    fn set_item_pinned(&self, id: i64, pinned: bool) -> impl Future<Output = Result<bool>> + Send;
 }
 
 impl Repository for SqlitePool {
+    async fn add_item_note(&self, item_id: i64, note: &NewItemNote) -> Result<Option<ItemNote>> {
+        query_as!(
+            ItemNote,
+            "INSERT INTO item_note (item_id, author, text) SELECT id, ?, ? FROM item WHERE id = ? RETURNING id, author, text, created_date",
+            note.author,
+            note.text,
+            item_id
+        )
+        .fetch_optional(self)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn add_item_tag(&self, item_id: i64, tag: &str) -> Result<bool> {
+        let mut tx = self.begin().await?;
+
+        let exists = query_scalar!("SELECT 1 FROM item WHERE id = ?", item_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .is_some();
+
+        if exists {
+            query!(
+                "INSERT OR IGNORE INTO item_tag (item_id, tag) VALUES (?, ?)",
+                item_id,
+                tag
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(exists)
+    }
+
+    async fn delete_saved_search(&self, name: &str) -> Result<bool> {
+        let result = query!("DELETE FROM saved_search WHERE name = ?", name)
+            .execute(self)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_facets(&self, filter: &ItemFilter) -> Result<Vec<FacetValue>> {
+        let query_tokens = filter.query.as_deref().map(tokenize_query);
+
+        let query_expression = query_tokens
+            .as_deref()
+            .map(parse_query)
+            .transpose()?
+            .flatten();
+
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            "WITH filtered AS (SELECT system, type, entity_event_id, user_agent FROM item WHERE 1 = 1",
+        );
+
+        builder.push_item_filter(filter, query_expression.as_ref()).push(
+            ") SELECT * FROM (\
+            SELECT 'system' facet, system value, COUNT(1) count FROM filtered GROUP BY system \
+            UNION ALL SELECT 'type', type, COUNT(1) FROM filtered GROUP BY type \
+            UNION ALL SELECT 'entityEventId', CAST(entity_event_id AS TEXT), COUNT(1) FROM filtered WHERE entity_event_id IS NOT NULL GROUP BY entity_event_id \
+            UNION ALL SELECT 'userAgent', user_agent, COUNT(1) FROM filtered GROUP BY user_agent\
+            ) ORDER BY facet, count DESC, value",
+        );
+
+        builder
+            .build_query_as()
+            .fetch_all(self)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn get_histogram(
+        &self,
+        filter: &ItemFilter,
+        bucket: HistogramBucket,
+        split: Option<HistogramSplit>,
+    ) -> Result<Vec<HistogramCount>> {
+        let query_tokens = filter.query.as_deref().map(tokenize_query);
+
+        let query_expression = query_tokens
+            .as_deref()
+            .map(parse_query)
+            .transpose()?
+            .flatten();
+
+        let format = match bucket {
+            HistogramBucket::Minute => "%Y-%m-%d %H:%M:00",
+            HistogramBucket::Hour => "%Y-%m-%d %H:00:00",
+            HistogramBucket::Day => "%Y-%m-%d",
+        };
+
+        let group = match split {
+            Some(HistogramSplit::System) => "system",
+            Some(HistogramSplit::Type) => "type",
+            None => "NULL",
+        };
+
+        let mut builder = QueryBuilder::<Sqlite>::new("SELECT strftime('");
+
+        builder
+            .push(format)
+            .push("', submit_date) start, ")
+            .push(group)
+            .push(" \"group\", COUNT(1) count FROM item WHERE 1 = 1")
+            .push_item_filter(filter, query_expression.as_ref())
+            .push(" GROUP BY 1, 2 ORDER BY 1, 2");
+
+        builder
+            .build_query_as()
+            .fetch_all(self)
+            .await
+            .map_err(Into::into)
+    }
+
     async fn get_item(&self, id: i64) -> Result<Option<Item>> {
         let summary = query_as!(
             ItemSummary,
-            "SELECT id, system, type, event_id, entity_event_id, user_agent, submit_date FROM item WHERE id = ?",
+            r#"SELECT id, system, type, event_id, entity_event_id, (SELECT event_group FROM event_type WHERE event_type.id = entity_event_id) AS "event_group?: String", (SELECT name FROM event_type WHERE event_type.id = entity_event_id) AS "event_type?: String", user_agent, path, submit_date, pinned AS "pinned: bool", valid AS "valid: bool", (SELECT json_group_array(tag ORDER BY tag) FROM item_tag WHERE item_id = id) AS "tags!: Json<Vec<String>>" FROM item WHERE id = ?"#,
             id
         ).fetch_optional(self).await?;
 
@@ -107,14 +580,41 @@ impl Repository for SqlitePool {
             .fetch_all(self)
             .await?;
 
+            let fields = query_as!(
+                ItemField,
+                "SELECT name, value FROM item_field WHERE item_id = ? ORDER BY name, value",
+                id
+            )
+            .fetch_all(self)
+            .await?;
+
+            let validation_errors = query_as!(
+                ItemValidationError,
+                "SELECT path, message FROM item_validation_error WHERE item_id = ? ORDER BY rowid",
+                id
+            )
+            .fetch_all(self)
+            .await?;
+
             let body = query_scalar!("SELECT body FROM item_body WHERE item_id = ?", id)
                 .fetch_one(self)
                 .await?;
 
+            let notes = query_as!(
+                ItemNote,
+                r#"SELECT id AS "id!", author, text, created_date FROM item_note WHERE item_id = ? ORDER BY id"#,
+                id
+            )
+            .fetch_all(self)
+            .await?;
+
             let item = Item {
                 summary,
                 headers,
+                fields,
+                validation_errors,
                 body,
+                notes,
             };
 
             Some(item)
@@ -125,103 +625,239 @@ impl Repository for SqlitePool {
         Ok(item)
     }
 
-    async fn get_items(&self, filter: &ItemFilter) -> Result<(Vec<ItemSummary>, i32)> {
-        #[derive(FromRow)]
-        struct Row {
-            #[sqlx(flatten)]
-            item: ItemSummary,
-            total_items: i32,
-        }
+    async fn get_items(&self, filter: &ItemFilter) -> Result<ItemPage> {
+        let query_tokens = filter.query.as_deref().map(tokenize_query);
 
-        let mut builder = QueryBuilder::<Sqlite>::new(
-            "SELECT * FROM (SELECT id, system, type, event_id, entity_event_id, user_agent, submit_date, COUNT(1) OVER() total_items FROM item WHERE 1 = 1",
-        );
+        let query_expression = query_tokens
+            .as_deref()
+            .map(parse_query)
+            .transpose()?
+            .flatten();
 
-        let query_tokens;
-
-        if let Some(query) = &filter.query {
-            query_tokens = tokenize_query(query);
-
-            builder.push(" AND (1 = 1");
-
-            for expression in query_tokens
-                .iter()
-                .map(|token| QueryExpression::from(token.as_ref()))
-            {
-                builder.push(" AND ");
-
-                match expression {
-                    QueryExpression::EventId(event_id) =>
-                        builder
-                            .push("event_id = ")
-                            .push_bind(event_id),
-                    QueryExpression::Header(name, value) =>
-                        builder
-                            .push("EXISTS (SELECT 1 FROM item_header WHERE item_id = id AND name = ")
-                            .push_bind(name)
-                            .push(" AND value LIKE '%' || ")
-                            .push_bind(value)
-                            .push(" || '%')"),
-                    QueryExpression::Id(id) =>
-                        builder
-                            .push("id = ")
-                            .push_bind(id),
-                    QueryExpression::Regex(regex) =>
-                        builder
-                            .push("EXISTS (SELECT 1 FROM item_body WHERE item_id = id AND matches(")
-                            .push_bind(regex)
-                            .push(", body))"),
-                    QueryExpression::Text(text) =>
-                        builder
-                            .push("EXISTS (SELECT 1 FROM item_body WHERE item_id = id AND body LIKE '%' || ")
-                            .push_bind(text)
-                            .push(" || '%')")
-                };
-            }
+        let mut sort = filter.sort.clone().unwrap_or_default();
 
-            builder.push(')');
+        if let Some(i) = sort.iter().position(|field| field.key == SortKey::Id) {
+            sort.truncate(i + 1);
+        } else {
+            sort.push(SortField {
+                key: SortKey::Id,
+                descending: !filter.asc.unwrap_or_default(),
+            });
         }
 
-        if let Some(systems) = &filter.system {
-            builder
-                .push(" AND system")
-                .append_in(systems.comma_separated());
-        }
+        let cursor = filter
+            .cursor
+            .as_deref()
+            .map(|cursor| Cursor::decode(cursor, filter))
+            .transpose()?;
 
-        if let Some(types) = &filter.r#type {
-            builder.push(" AND type").append_in(types.comma_separated());
+        if let Some(cursor) = &cursor
+            && cursor.position.len() != sort.len()
+        {
+            return Err(CursorError::new("invalid cursor").into());
         }
 
-        if let Some(event_types) = &filter.event_type {
+        let backward = cursor.as_ref().is_some_and(|cursor| cursor.backward);
+
+        let order: Vec<_> = sort
+            .iter()
+            .map(|field| SortField {
+                key: field.key,
+                descending: field.descending != backward,
+            })
+            .collect();
+
+        let mut builder = QueryBuilder::<Sqlite>::new("SELECT *");
+
+        for (i, field) in sort.iter().enumerate() {
             builder
-                .push(
-                    " AND (type NOT IN ('event_notification', 'event_payload') OR entity_event_id",
-                )
-                .append_in(event_types.comma_separated())
-                .push(')');
+                .push(", ")
+                .push(sort_expression(field.key))
+                .push(format!(" AS sort_{i}"));
         }
 
         builder
-            .append_if_is_some(" AND submit_date >= ", filter.from.as_ref())
-            .append_if_is_some(" AND submit_date <= ", filter.to.as_ref())
+            .push(" FROM (SELECT id, system, type, event_id, entity_event_id, (SELECT event_group FROM event_type WHERE event_type.id = entity_event_id) event_group, (SELECT name FROM event_type WHERE event_type.id = entity_event_id) event_type, user_agent, path, submit_date, pinned, valid, (SELECT json_group_array(tag ORDER BY tag) FROM item_tag WHERE item_id = id) tags FROM item WHERE 1 = 1")
+            .push_item_filter(filter, query_expression.as_ref())
             .push(") WHERE 1 = 1")
             .append_if_is_some(" AND id >= ", filter.first_item_id)
-            .append_if_is_some(" AND id <= ", filter.last_item_id)
-            .push(" ORDER BY id");
+            .append_if_is_some(" AND id <= ", filter.last_item_id);
+
+        if let Some(cursor) = &cursor {
+            builder.push(" AND ").push_keyset(&order, |builder, i| {
+                builder.push_cursor_value(&cursor.position[i]);
+            });
+        } else if let Some(after_item_id) = filter.after_item_id {
+            builder.push(" AND ").push_keyset(&order, |builder, i| {
+                builder
+                    .push("(SELECT ")
+                    .push(sort_expression(order[i].key))
+                    .push(" FROM item WHERE id = ")
+                    .push_bind(after_item_id)
+                    .push(')');
+            });
+        }
+
+        builder.push(" ORDER BY ");
+
+        for (i, field) in order.iter().enumerate() {
+            if i > 0 {
+                builder.push(", ");
+            }
+
+            builder.push(format!("sort_{i}"));
 
-        if !filter.asc.unwrap_or_default() {
-            builder.push(" DESC");
+            if field.descending {
+                builder.push(" DESC");
+            }
+        }
+
+        builder.append_if_is_some(" LIMIT ", filter.batch_size.map(|size| i64::from(size) + 1));
+
+        let mut rows = builder.build().fetch_all(self).await?;
+
+        let has_more = filter
+            .batch_size
+            .is_some_and(|size| rows.len() > size as usize);
+
+        if has_more {
+            rows.pop();
         }
 
-        builder.append_if_is_some(" LIMIT ", filter.batch_size);
+        if backward {
+            rows.reverse();
+        }
+
+        let paged = cursor.is_some() || filter.after_item_id.is_some();
+        let (has_next, has_prev) = if backward {
+            (paged, has_more)
+        } else {
+            (has_more, paged)
+        };
+
+        let position = |row: &SqliteRow| {
+            sort.iter()
+                .enumerate()
+                .map(|(i, field)| {
+                    let column = format!("sort_{i}");
+
+                    Ok(match field.key {
+                        SortKey::SubmitDate
+                        | SortKey::System
+                        | SortKey::Type
+                        | SortKey::UserAgent => CursorValue::Text(row.try_get(column.as_str())?),
+                        _ => CursorValue::Integer(row.try_get(column.as_str())?),
+                    })
+                })
+                .collect::<Result<Vec<_>>>()
+        };
+
+        let next = rows
+            .last()
+            .filter(|_| has_next)
+            .map(|row| Ok::<_, Error>(Cursor::new(filter, false, position(row)?).to_string()))
+            .transpose()?;
+
+        let prev = rows
+            .first()
+            .filter(|_| has_prev)
+            .map(|row| Ok::<_, Error>(Cursor::new(filter, true, position(row)?).to_string()))
+            .transpose()?;
+
+        let (total_items, total_items_capped) =
+            count_items(self, filter, query_expression.as_ref()).await?;
+
+        let items = rows
+            .iter()
+            .map(ItemSummary::from_row)
+            .collect::<Result<_, _>>()?;
+
+        Ok(ItemPage {
+            items,
+            total_items,
+            total_items_capped,
+            next,
+            prev,
+        })
+    }
+
+    async fn get_related_items(&self, id: i64) -> Result<Vec<ItemSummary>> {
+        let items = query_as!(
+            ItemSummary,
+            r#"SELECT id, system, type, event_id, entity_event_id, (SELECT event_group FROM event_type WHERE event_type.id = entity_event_id) AS "event_group?: String", (SELECT name FROM event_type WHERE event_type.id = entity_event_id) AS "event_type?: String", user_agent, path, submit_date, pinned AS "pinned: bool", valid AS "valid: bool", (SELECT json_group_array(tag ORDER BY tag) FROM item_tag WHERE item_id = id) AS "tags!: Json<Vec<String>>" FROM item WHERE id = ? OR event_id = (SELECT event_id FROM item WHERE id = ?) ORDER BY submit_date, id LIMIT ?"#,
+            id,
+            id,
+            EVENT_ITEMS_LIMIT
+        )
+        .fetch_all(self)
+        .await?;
 
-        let query = builder.build_query_as::<Row>();
-        let rows = query.fetch_all(self).await?;
+        Ok(items)
+    }
 
-        let total_items = rows.first().map(|row| row.total_items).unwrap_or_default();
-        let items = rows.into_iter().map(|row| row.item).collect();
+    async fn get_saved_search(&self, name: &str) -> Result<Option<SavedSearch>> {
+        query_as!(
+            SavedSearchRow,
+            "SELECT name, owner, description, filter, created_date, updated_date FROM saved_search WHERE name = ?",
+            name
+        )
+        .fetch_optional(self)
+        .await?
+        .map(TryInto::try_into)
+        .transpose()
+    }
 
-        Ok((items, total_items))
+    async fn get_saved_searches(&self) -> Result<Vec<SavedSearch>> {
+        query_as!(
+            SavedSearchRow,
+            "SELECT name, owner, description, filter, created_date, updated_date FROM saved_search ORDER BY name"
+        )
+        .fetch_all(self)
+        .await?
+        .into_iter()
+        .map(TryInto::try_into)
+        .collect()
+    }
+
+    async fn get_stats(&self) -> Result<Stats> {
+        let (total_items, total_bytes, items_last_hour) = query_as(
+            "SELECT \
+                (SELECT COUNT(1) FROM item), \
+                (SELECT IFNULL(SUM(length(body)), 0) FROM item_body), \
+                (SELECT COUNT(1) FROM item WHERE submit_date >= datetime('now', '-1 hour'))",
+        )
+        .fetch_one(self)
+        .await?;
+
+        let systems = query_as(
+            "SELECT system, COUNT(1) count, IFNULL(AVG(length(body)), 0.0) average_size, MIN(submit_date) first_seen, MAX(submit_date) last_seen \
+            FROM item LEFT JOIN item_body ON item_id = id GROUP BY system ORDER BY count DESC, system",
+        )
+        .fetch_all(self)
+        .await?;
+
+        let types = query_as(
+            "SELECT type, COUNT(1) count, IFNULL(AVG(length(body)), 0.0) average_size \
+            FROM item LEFT JOIN item_body ON item_id = id GROUP BY type ORDER BY count DESC, type",
+        )
+        .fetch_all(self)
+        .await?;
+
+        let user_agents = query_as(
+            "SELECT user_agent, COUNT(1) count FROM item WHERE user_agent IS NOT NULL \
+            GROUP BY user_agent ORDER BY count DESC, user_agent LIMIT 10",
+        )
+        .fetch_all(self)
+        .await?;
+
+        Ok(Stats {
+            total_items,
+            total_bytes,
+            items_last_hour,
+            systems,
+            types,
+            user_agents,
+        })
     }
 
     async fn get_systems(&self) -> Result<Vec<String>> {
@@ -233,16 +869,37 @@ impl Repository for SqlitePool {
         .map_err(Into::into)
     }
 
+    async fn get_timeline(&self, event_id: i64) -> Result<Vec<TimelineStep>> {
+        query_as(
+            "SELECT id, system, type, event_id, entity_event_id, user_agent, path, submit_date, pinned, valid, \
+                (SELECT event_group FROM event_type WHERE event_type.id = entity_event_id) event_group, \
+                (SELECT name FROM event_type WHERE event_type.id = entity_event_id) event_type, \
+                (SELECT json_group_array(tag ORDER BY tag) FROM item_tag WHERE item_id = id) tags, \
+                CAST(round((julianday(submit_date) - julianday(LAG(submit_date) OVER (ORDER BY submit_date, id))) * 86400) AS INTEGER) gap_seconds \
+            FROM item \
+            WHERE event_id = ? \
+            ORDER BY submit_date, id \
+            LIMIT ?",
+        )
+        .bind(event_id)
+        .bind(EVENT_ITEMS_LIMIT)
+        .fetch_all(self)
+        .await
+        .map_err(Into::into)
+    }
+
     async fn insert_item(&self, item: &NewItem<'_>) -> Result<i64> {
         let mut tx = self.begin().await?;
 
         let id = query!(
-            "INSERT INTO item (system, type, event_id, entity_event_id, user_agent) VALUES (?, ?, ?, ?, ?)",
+            "INSERT INTO item (system, type, event_id, entity_event_id, user_agent, path, valid) VALUES (?, ?, ?, ?, ?, ?, ?)",
             item.system,
             item.r#type,
             item.event_id,
             item.entity_event_id,
-            item.user_agent
+            item.user_agent,
+            item.path,
+            item.valid
         )
         .execute(&mut *tx)
         .await?
@@ -259,6 +916,28 @@ impl Repository for SqlitePool {
             .await?;
         }
 
+        for field in item.fields {
+            query!(
+                "INSERT OR IGNORE INTO item_field (item_id, name, value) VALUES (?, ?, ?)",
+                id,
+                field.name,
+                field.value
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for error in item.validation_errors {
+            query!(
+                "INSERT INTO item_validation_error (item_id, path, message) VALUES (?, ?, ?)",
+                id,
+                error.path,
+                error.message
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
         query!(
             "INSERT INTO item_body (item_id, body) VALUES (?, ?)",
             id,
@@ -271,6 +950,159 @@ impl Repository for SqlitePool {
 
         Ok(id)
     }
+
+    async fn remove_item_note(&self, item_id: i64, note_id: i64) -> Result<bool> {
+        let result = query!(
+            "DELETE FROM item_note WHERE id = ? AND item_id = ?",
+            note_id,
+            item_id
+        )
+        .execute(self)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn remove_item_tag(&self, item_id: i64, tag: &str) -> Result<bool> {
+        let result = query!(
+            "DELETE FROM item_tag WHERE item_id = ? AND tag = ?",
+            item_id,
+            tag
+        )
+        .execute(self)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn save_saved_search(&self, name: &str, search: &NewSavedSearch) -> Result<()> {
+        let filter = search.filter.to_string();
+
+        query!(
+            "INSERT INTO saved_search (name, owner, description, filter) VALUES (?, ?, ?, ?) \
+            ON CONFLICT (name) DO UPDATE SET owner = excluded.owner, description = excluded.description, filter = excluded.filter, updated_date = CURRENT_TIMESTAMP",
+            name,
+            search.owner,
+            search.description,
+            filter
+        )
+        .execute(self)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn set_event_types(&self, groups: &[EventTypeGroup]) -> Result<()> {
+        let mut tx = self.begin().await?;
+
+        query!("DELETE FROM event_type").execute(&mut *tx).await?;
+
+        for group in groups {
+            for event_type in &group.types {
+                query!(
+                    "INSERT INTO event_type (id, event_group, name) VALUES (?, ?, ?)",
+                    event_type.id,
+                    group.name,
+                    event_type.name
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn set_item_classification(
+        &self,
+        id: i64,
+        classification: &ItemClassification,
+    ) -> Result<bool> {
+        let mut tx = self.begin().await?;
+
+        let updated = query!(
+            "UPDATE item SET system = ?, type = ?, entity_event_id = ?, valid = ? WHERE id = ?",
+            classification.system,
+            classification.r#type,
+            classification.entity_event_id,
+            classification.valid,
+            id
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            > 0;
+
+        if updated {
+            query!("DELETE FROM item_field WHERE item_id = ?", id)
+                .execute(&mut *tx)
+                .await?;
+
+            for field in &classification.fields {
+                query!(
+                    "INSERT OR IGNORE INTO item_field (item_id, name, value) VALUES (?, ?, ?)",
+                    id,
+                    field.name,
+                    field.value
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+
+            query!("DELETE FROM item_validation_error WHERE item_id = ?", id)
+                .execute(&mut *tx)
+                .await?;
+
+            for error in &classification.validation_errors {
+                query!(
+                    "INSERT INTO item_validation_error (item_id, path, message) VALUES (?, ?, ?)",
+                    id,
+                    error.path,
+                    error.message
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(updated)
+    }
+
+    async fn set_item_pinned(&self, id: i64, pinned: bool) -> Result<bool> {
+        let result = query!("UPDATE item SET pinned = ? WHERE id = ?", pinned, id)
+            .execute(self)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+struct SavedSearchRow {
+    name: String,
+    owner: Option<String>,
+    description: Option<String>,
+    filter: String,
+    created_date: String,
+    updated_date: String,
+}
+
+impl TryFrom<SavedSearchRow> for SavedSearch {
+    type Error = Error;
+
+    fn try_from(row: SavedSearchRow) -> Result<Self> {
+        Ok(Self {
+            name: row.name,
+            owner: row.owner,
+            description: row.description,
+            filter: serde_json::from_str(&row.filter)?,
+            created_date: row.created_date,
+            updated_date: row.updated_date,
+        })
+    }
 }
 
 trait SplitExt {
@@ -312,77 +1144,228 @@ pub async unsafe fn register_match_function(connection: &mut SqliteConnection) -
         let mut handle = connection.lock_handle().await?;
         let connection = Connection::from_handle(handle.as_raw_handle().as_mut())?;
 
-        connection
-            .create_scalar_function("matches", 2, FunctionFlags::SQLITE_DETERMINISTIC, |ctx| {
+        connection.create_scalar_function(
+            "matches",
+            2,
+            FunctionFlags::SQLITE_DETERMINISTIC,
+            |ctx| {
                 let regex = ctx.get_or_create_aux(0, |vr| -> Result<Regex> {
                     let regex = vr.as_str()?;
                     Regex::new(regex).map_err(Into::into)
                 })?;
 
-                let text = ctx.get_raw(1).as_bytes()?;
+                let text = match ctx.get_raw(1) {
+                    ValueRef::Null => return Ok(false),
+                    value => value.as_bytes()?,
+                };
+
                 Ok(regex.is_match(text))
-            })
+            },
+        )?;
+
+        connection.create_scalar_function(
+            "json_matches",
+            3,
+            FunctionFlags::SQLITE_DETERMINISTIC,
+            |ctx| {
+                let path = ctx.get_or_create_aux(0, |vr| -> Result<JsonPath> {
+                    JsonPath::parse(vr.as_str()?).map_err(Into::into)
+                })?;
+
+                let pattern = ctx.get_or_create_aux(1, |vr| -> Result<Option<ValuePattern>> {
+                    vr.as_str_or_null()?.map(ValuePattern::new).transpose()
+                })?;
+
+                let Ok(body) = serde_json::from_slice::<Value>(ctx.get_raw(2).as_bytes()?) else {
+                    return Ok(false);
+                };
+
+                let nodes = path.query(&body);
+
+                Ok(match pattern.as_ref() {
+                    Some(pattern) => nodes.iter().any(|node| pattern.is_json_match(node)),
+                    None => !nodes.is_empty(),
+                })
+            },
+        )?;
+
+        connection
+            .create_scalar_function(
+                "xpath_matches",
+                3,
+                FunctionFlags::SQLITE_DETERMINISTIC,
+                |ctx| {
+                    let path = ctx.get_raw(0).as_str()?;
+
+                    let pattern =
+                        ctx.get_or_create_aux(1, |vr| -> Result<Option<ValuePattern>> {
+                            vr.as_str_or_null()?.map(ValuePattern::new).transpose()
+                        })?;
+
+                    let Ok(body) = str::from_utf8(ctx.get_raw(2).as_bytes()?) else {
+                        return Ok(false);
+                    };
+
+                    let Some(package) = xml::parse(body) else {
+                        return Ok(false);
+                    };
+
+                    let document = package.as_document();
+
+                    xml::with_xpath(path, |xpath| {
+                        let Some(Ok(value)) =
+                            xpath.map(|xpath| xpath.evaluate(&Context::new(), document.root()))
+                        else {
+                            return false;
+                        };
+
+                        match pattern.as_ref() {
+                            Some(pattern) => pattern.is_xpath_match(&value),
+                            None => value.boolean(),
+                        }
+                    })
+                    .map_err(|e| rusqlite::Error::UserFunctionError(e.into()))
+                },
+            )
             .map_err(Into::into)
     }
 }
 
-fn tokenize_query(query: &str) -> Vec<Cow<'_, str>> {
-    let mut tokens = Vec::new();
-    let mut chars = query.char_indices();
-    let mut quoted_token = String::new();
+enum ValuePattern {
+    Exact(String),
+    Regex(Regex),
+}
 
-    while let Some((start, c)) = chars.next() {
-        if c.is_whitespace() {
-            continue;
+impl ValuePattern {
+    fn new(pattern: &str) -> Result<Self> {
+        match pattern.split_at_checked(1) {
+            Some(("=", value)) => Ok(Self::Exact(value.into())),
+            Some(("~", regex)) => Ok(Self::Regex(Regex::new(regex)?)),
+            _ => Err(anyhow!("invalid value pattern '{pattern}'")),
         }
+    }
 
-        let mut cc = c;
+    fn is_json_match(&self, node: &Value) -> bool {
+        match node {
+            Value::Number(number) => number
+                .as_f64()
+                .is_some_and(|number| self.is_number_match(number)),
+            Value::String(text) => self.is_match(text),
+            node => self.is_match(&node.to_string()),
+        }
+    }
 
-        loop {
-            if cc == '"' {
-                while let Some((_, c)) = chars.next() {
-                    if c == '"' {
-                        if let Some((_, '"')) = chars.clone().next() {
-                            chars.next();
-                        } else {
-                            break;
-                        }
-                    }
+    fn is_match(&self, text: &str) -> bool {
+        match self {
+            Self::Exact(value) => text == value,
+            Self::Regex(regex) => regex.is_match(text.as_bytes()),
+        }
+    }
 
-                    quoted_token.push(c);
-                }
+    fn is_number_match(&self, number: f64) -> bool {
+        match self {
+            Self::Exact(value) => value.parse::<f64>().is_ok_and(|value| value == number),
+            Self::Regex(_) => self.is_match(&number.to_string()),
+        }
+    }
 
-                tokens.push(take(&mut quoted_token).into());
-                break;
+    fn is_xpath_match(&self, value: &XPathValue<'_>) -> bool {
+        match value {
+            XPathValue::Boolean(boolean) => self.is_match(&boolean.to_string()),
+            XPathValue::Nodeset(nodes) => {
+                nodes.iter().any(|node| self.is_match(&node.string_value()))
             }
+            XPathValue::Number(number) => self.is_number_match(*number),
+            XPathValue::String(text) => self.is_match(text),
+        }
+    }
+}
 
-            let mut end = start;
+async fn count_items(
+    pool: &SqlitePool,
+    filter: &ItemFilter,
+    expression: Option<&QueryExpression<'_>>,
+) -> Result<(Option<i32>, bool)> {
+    let mode = filter.count.unwrap_or_default();
 
-            for (i, c) in chars.by_ref() {
-                if c == '"' || c.is_whitespace() {
-                    cc = c;
-                    break;
-                }
+    if mode == CountMode::None {
+        return Ok((None, false));
+    }
 
-                end = i;
-            }
+    let use_counters = expression.is_none()
+        && filter.event_type.is_none()
+        && filter.pinned.is_none()
+        && filter.valid.is_none()
+        && filter.from.is_none()
+        && filter.to.is_none()
+        && [&filter.system, &filter.r#type].iter().all(|values| {
+            values
+                .as_deref()
+                .is_none_or(|values| values.comma_separated().all(|value| !value.is_empty()))
+        });
 
-            tokens.push(Cow::from(&query[start..=end]));
+    let mut builder;
 
-            if cc != '"' {
-                break;
-            }
+    if use_counters {
+        builder =
+            QueryBuilder::<Sqlite>::new("SELECT IFNULL(SUM(count), 0) FROM item_count WHERE 1 = 1");
+
+        if let Some(systems) = &filter.system {
+            builder
+                .push(" AND system")
+                .append_in(systems.comma_separated());
+        }
+
+        if let Some(types) = &filter.r#type {
+            builder.push(" AND type").append_in(types.comma_separated());
         }
+    } else {
+        builder =
+            QueryBuilder::<Sqlite>::new("SELECT COUNT(1) FROM (SELECT 1 FROM item WHERE 1 = 1");
+        builder.push_item_filter(filter, expression);
+
+        if mode == CountMode::Estimated {
+            builder.push(" LIMIT ").push_bind(ESTIMATED_COUNT_LIMIT + 1);
+        }
+
+        builder.push(')');
+    }
+
+    let count: i32 = builder.build_query_scalar().fetch_one(pool).await?;
+
+    if count > ESTIMATED_COUNT_LIMIT && mode == CountMode::Estimated && !use_counters {
+        Ok((Some(ESTIMATED_COUNT_LIMIT), true))
+    } else {
+        Ok((Some(count), false))
+    }
+}
+
+fn sort_expression(key: SortKey) -> &'static str {
+    match key {
+        SortKey::EntityEventId => "IFNULL(entity_event_id, -9223372036854775808)",
+        SortKey::EventId => "IFNULL(event_id, -9223372036854775808)",
+        SortKey::Id => "id",
+        SortKey::Size => "IFNULL((SELECT length(body) FROM item_body WHERE item_id = id), 0)",
+        SortKey::SubmitDate => "submit_date",
+        SortKey::System => "IFNULL(system, '')",
+        SortKey::Type => "IFNULL(type, '')",
+        SortKey::UserAgent => "IFNULL(user_agent, '')",
     }
+}
 
-    tokens
+fn value_pattern(pattern: Option<&Pattern<'_>>) -> Option<String> {
+    pattern.map(|pattern| match pattern {
+        Pattern::Regex(regex) => format!("~{regex}"),
+        Pattern::Contains(value) | Pattern::Exact(value) | Pattern::Wildcard(value) => {
+            format!("={value}")
+        }
+    })
 }
 
 #[cfg(test)]
 pub mod tests {
     use super::*;
     use mockall::mock;
-    use rstest::rstest;
 
     mock! {
         pub Repository{}
@@ -392,63 +1375,78 @@ pub mod tests {
         }
 
         impl super::Repository for Repository {
+            fn add_item_note(
+                &self,
+                item_id: i64,
+                note: &NewItemNote,
+            ) -> impl Future<Output = Result<Option<ItemNote>>> + Send;
+
+            fn add_item_tag(&self, item_id: i64, tag: &str) -> impl Future<Output = Result<bool>> + Send;
+            fn delete_saved_search(&self, name: &str) -> impl Future<Output = Result<bool>> + Send;
+
+            fn get_facets(
+                &self,
+                filter: &ItemFilter,
+            ) -> impl Future<Output = Result<Vec<FacetValue>>> + Send;
+
+            fn get_histogram(
+                &self,
+                filter: &ItemFilter,
+                bucket: HistogramBucket,
+                split: Option<HistogramSplit>,
+            ) -> impl Future<Output = Result<Vec<HistogramCount>>> + Send;
+
             fn get_item(&self, id: i64) -> impl Future<Output = Result<Option<Item>>> + Send;
 
             fn get_items(
                 &self,
                 filter: &ItemFilter,
-            ) -> impl Future<Output = Result<(Vec<ItemSummary>, i32)>> + Send;
+            ) -> impl Future<Output = Result<ItemPage>> + Send;
 
+            fn get_related_items(
+                &self,
+                id: i64,
+            ) -> impl Future<Output = Result<Vec<ItemSummary>>> + Send;
+
+            fn get_saved_search(
+                &self,
+                name: &str,
+            ) -> impl Future<Output = Result<Option<SavedSearch>>> + Send;
+
+            fn get_saved_searches(&self) -> impl Future<Output = Result<Vec<SavedSearch>>> + Send;
+            fn get_stats(&self) -> impl Future<Output = Result<Stats>> + Send;
             fn get_systems(&self) -> impl Future<Output = Result<Vec<String>>> + Send;
+
+            fn get_timeline(
+                &self,
+                event_id: i64,
+            ) -> impl Future<Output = Result<Vec<TimelineStep>>> + Send;
+
             fn insert_item<'a>(&self, item: &NewItem<'a>) -> impl Future<Output = Result<i64>> + Send;
-        }
-    }
 
-    #[test]
-    fn test_query_expressions() {
-        let tokens = tokenize_query(r#"event-id:123 id:123 regex:abc abc:def abc "abc def""#);
+            fn remove_item_note(
+                &self,
+                item_id: i64,
+                note_id: i64,
+            ) -> impl Future<Output = Result<bool>> + Send;
 
-        let mut iter = tokens
-            .iter()
-            .map(|token| QueryExpression::from(token.as_ref()));
-
-        assert_eq!(iter.next(), Some(QueryExpression::EventId("123")));
-        assert_eq!(iter.next(), Some(QueryExpression::Id("123")));
-        assert_eq!(iter.next(), Some(QueryExpression::Regex("abc")));
-        assert_eq!(iter.next(), Some(QueryExpression::Header("abc", "def")));
-        assert_eq!(iter.next(), Some(QueryExpression::Text("abc")));
-        assert_eq!(iter.next(), Some(QueryExpression::Text("abc def")));
-        assert_eq!(iter.next(), None);
-    }
-
-    #[rstest]
-    #[case("", &[])]
-    #[case(" ", &[])]
-    #[case("a", &["a"])]
-    #[case(" a ", &["a"])]
-    #[case("a b", &["a", "b"])]
-    #[case(" a b ", &["a", "b"])]
-    #[case("abc", &["abc"])]
-    #[case(" abc ", &["abc"])]
-    #[case("abc def", &["abc", "def"])]
-    #[case(" abc def ", &["abc", "def"])]
-    #[case(r#""""#, &[""])]
-    #[case(r#""a""#, &["a"])]
-    #[case(r#"""#, &[""])]
-    #[case(r#""a"#, &["a"])]
-    #[case(r#"" a ""#, &[" a "])]
-    #[case(r#""abc""#, &["abc"])]
-    #[case(r#"" abc def ""#, &[" abc def "])]
-    #[case(r#""a" "b""#, &["a", "b"])]
-    #[case(r#"a"b"c"#, &["a", "b", "c"])]
-    #[case(r#""a"b"#, &["a", "b"])]
-    #[case(r#"""a"b"#, &["", "a", "b"])]
-    #[case(r#""""a"b"#, &[r#""a"#, "b"])]
-    #[case(r#"""""a"b"#, &[r#"""#, "a", "b"])]
-    #[case(r#""""""a"b"#, &[r#"""a"#, "b"])]
-    #[case(r#""""""#, &[r#"""#])]
-    #[case(r#""regex:abc "" def""#, &[r#"regex:abc " def"#])]
-    fn test_tokenize_query(#[case] query: &str, #[case] expected_terms: &[&str]) {
-        assert_eq!(tokenize_query(query), expected_terms, "query = {query}");
+            fn remove_item_tag(&self, item_id: i64, tag: &str) -> impl Future<Output = Result<bool>> + Send;
+
+            fn save_saved_search(
+                &self,
+                name: &str,
+                search: &NewSavedSearch,
+            ) -> impl Future<Output = Result<()>> + Send;
+
+            fn set_event_types(&self, groups: &[EventTypeGroup]) -> impl Future<Output = Result<()>> + Send;
+
+            fn set_item_classification(
+                &self,
+                id: i64,
+                classification: &ItemClassification,
+            ) -> impl Future<Output = Result<bool>> + Send;
+
+            fn set_item_pinned(&self, id: i64, pinned: bool) -> impl Future<Output = Result<bool>> + Send;
+        }
     }
 }
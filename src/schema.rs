@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+use anyhow::{Result, anyhow};
+use jsonschema::Validator;
+use serde_json::Value;
+
+use crate::model::ItemValidationError;
+
+pub struct ItemSchemas(HashMap<String, Validator>);
+
+impl ItemSchemas {
+    pub fn builtin() -> Result<Self> {
+        Self::parse(include_str!("../item.schemas.json"))
+    }
+
+    pub fn parse(json: &str) -> Result<Self> {
+        let schemas: HashMap<String, Value> = serde_json::from_str(json)?;
+
+        schemas
+            .into_iter()
+            .map(|(item_type, schema)| {
+                let validator = jsonschema::validator_for(&schema)
+                    .map_err(|e| anyhow!("invalid schema for item type '{item_type}': {e}"))?;
+
+                Ok((item_type, validator))
+            })
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+
+    pub fn validate(
+        &self,
+        item_type: Option<&str>,
+        body: &[u8],
+    ) -> Option<Vec<ItemValidationError>> {
+        let validator = self.0.get(item_type?)?;
+
+        let errors = match serde_json::from_slice::<Value>(body) {
+            Ok(instance) => validator
+                .iter_errors(&instance)
+                .map(|error| ItemValidationError {
+                    path: error.instance_path().as_str().into(),
+                    message: error.to_string(),
+                })
+                .collect(),
+            Err(e) => vec![ItemValidationError {
+                path: String::new(),
+                message: format!("invalid JSON: {e}"),
+            }],
+        };
+
+        Some(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("", false)]
+    #[case("[]", false)]
+    #[case("{}", true)]
+    #[case(r#"{"a": {"type": "object"}}"#, true)]
+    #[case(r#"{"a": {"type": "unknown"}}"#, false)]
+    fn test_parse(#[case] json: &str, #[case] expected_valid: bool) {
+        assert_eq!(ItemSchemas::parse(json).is_ok(), expected_valid);
+    }
+
+    #[test]
+    fn test_builtin() {
+        assert!(ItemSchemas::builtin().is_ok());
+    }
+
+    #[rstest]
+    #[case(None, b"", None)]
+    #[case(Some("b"), b"", None)]
+    #[case(Some("a"), br#"{"entityEventId": 1}"#, Some(&[][..]))]
+    #[case(Some("a"), br#"{}"#, Some(&[("", "\"entityEventId\" is a required property")][..]))]
+    #[case(Some("a"), br#"{"entityEventId": "1"}"#, Some(&[("/entityEventId", "\"1\" is not of type \"integer\"")][..]))]
+    #[case(Some("a"), b"<a/>", Some(&[("", "invalid JSON: expected value at line 1 column 1")][..]))]
+    fn test_validate(
+        #[case] item_type: Option<&str>,
+        #[case] body: &[u8],
+        #[case] expected_errors: Option<&[(&str, &str)]>,
+    ) -> Result<()> {
+        let schemas = ItemSchemas::parse(
+            r#"{
+                "a": {
+                    "type": "object",
+                    "required": ["entityEventId"],
+                    "properties": {"entityEventId": {"type": "integer"}}
+                }
+            }"#,
+        )?;
+
+        let errors = schemas.validate(item_type, body).map(|errors| {
+            errors
+                .into_iter()
+                .map(|error| (error.path, error.message))
+                .collect::<Vec<_>>()
+        });
+
+        assert_eq!(
+            errors,
+            expected_errors.map(|errors| {
+                errors
+                    .iter()
+                    .map(|(path, message)| ((*path).to_string(), (*message).to_string()))
+                    .collect()
+            })
+        );
+
+        Ok(())
+    }
+}
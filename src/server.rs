@@ -10,14 +10,14 @@ use anyhow::{Error, Result};
 use axum::{
     Json, Router,
     body::{Body, Bytes},
-    extract::{ConnectInfo, Path, Query, State},
+    extract::{ConnectInfo, OriginalUri, Path, Query, State},
     http::{
         HeaderMap, HeaderName, HeaderValue, Request, StatusCode, Uri,
         header::{CACHE_CONTROL, CONTENT_TYPE},
         uri::PathAndQuery,
     },
     response::{IntoResponse, Redirect, Response},
-    routing::get,
+    routing::{delete, get, post, put},
     serve,
 };
 
@@ -27,10 +27,15 @@ use serde::Serialize;
 use tokio::net::TcpListener;
 use tower::ServiceBuilder;
 use tower_http::{compression::CompressionLayer, trace::TraceLayer};
-use tracing::{Span, error, error_span, field, instrument, trace};
+use tracing::{Span, error, error_span, field, instrument, trace, warn};
 
 use crate::{
-    model::{ItemFilter, NewItemHeader},
+    cursor::CursorError,
+    model::{
+        DiffParams, HistogramParams, ItemFilter, NewItemHeader, NewItemNote, NewSavedSearch,
+        NotFoundError, ReclassifyParams, ValidationError,
+    },
+    query::QueryError,
     service::Service,
 };
 
@@ -50,8 +55,18 @@ where
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let error = format!("{}", self.0);
-        error!(error);
-        (StatusCode::INTERNAL_SERVER_ERROR, error).into_response()
+
+        if self.0.is::<CursorError>() || self.0.is::<QueryError>() || self.0.is::<ValidationError>()
+        {
+            warn!(error);
+            (StatusCode::BAD_REQUEST, error).into_response()
+        } else if self.0.is::<NotFoundError>() {
+            warn!(error);
+            (StatusCode::NOT_FOUND, error).into_response()
+        } else {
+            error!(error);
+            (StatusCode::INTERNAL_SERVER_ERROR, error).into_response()
+        }
     }
 }
 
@@ -114,6 +129,59 @@ async fn get_index_html<S: Service>(
     respond_with_data(items)
 }
 
+#[instrument(skip_all, fields(id))]
+async fn add_item_note<S: Service>(
+    State(service): State<S>,
+    Path(id): Path<i64>,
+    Json(note): Json<NewItemNote>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(service.add_item_note(id, &note).await?.map_or_else(
+        || StatusCode::NOT_FOUND.into_response(),
+        |note| Json(note).into_response(),
+    ))
+}
+
+#[instrument(skip_all, fields(id, tag))]
+async fn add_item_tag<S: Service>(
+    State(service): State<S>,
+    Path((id, tag)): Path<(i64, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(status(service.add_item_tag(id, &tag).await?))
+}
+
+#[instrument(skip_all, fields(name))]
+async fn delete_saved_search<S: Service>(
+    State(service): State<S>,
+    Path(name): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(status(service.delete_saved_search(&name).await?))
+}
+
+#[instrument(skip_all)]
+async fn diff_items<S: Service>(
+    State(service): State<S>,
+    Query(params): Query<DiffParams>,
+) -> impl IntoResponse {
+    service.diff_items(&params).await.to_json_response()
+}
+
+#[instrument(skip_all)]
+async fn get_event_types<S: Service>(State(service): State<S>) -> impl IntoResponse {
+    service.get_event_types().await.to_json_response()
+}
+
+#[instrument(skip_all, fields(filter))]
+async fn get_histogram<S: Service>(
+    State(service): State<S>,
+    Query(filter): Query<ItemFilter>,
+    Query(params): Query<HistogramParams>,
+) -> impl IntoResponse {
+    service
+        .get_histogram(&filter, &params)
+        .await
+        .to_json_response()
+}
+
 #[instrument(skip_all, fields(id))]
 async fn get_item<S: Service>(
     State(service): State<S>,
@@ -136,6 +204,11 @@ async fn get_item_html<S: Service>(
     ))
 }
 
+#[instrument(skip_all)]
+async fn get_item_types<S: Service>(State(service): State<S>) -> impl IntoResponse {
+    service.get_item_types().await.to_json_response()
+}
+
 #[instrument(skip_all, fields(filter))]
 async fn get_items<S: Service>(
     State(service): State<S>,
@@ -171,6 +244,69 @@ async fn get_raw_item<S: Service>(
     Ok(response)
 }
 
+#[instrument(skip_all, fields(id))]
+async fn get_related_items<S: Service>(
+    State(service): State<S>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(service.get_related_items(id).await?.map_or_else(
+        || StatusCode::NOT_FOUND.into_response(),
+        |items| Json(items).into_response(),
+    ))
+}
+
+#[instrument(skip_all, fields(name))]
+async fn get_saved_search<S: Service>(
+    State(service): State<S>,
+    Path(name): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(service.get_saved_search(&name).await?.map_or_else(
+        || StatusCode::NOT_FOUND.into_response(),
+        |search| Json(search).into_response(),
+    ))
+}
+
+#[instrument(skip_all)]
+async fn get_saved_searches<S: Service>(State(service): State<S>) -> impl IntoResponse {
+    service.get_saved_searches().await.to_json_response()
+}
+
+#[instrument(skip_all)]
+async fn get_stats<S: Service>(State(service): State<S>) -> impl IntoResponse {
+    service.get_stats().await.to_json_response()
+}
+
+#[instrument(skip_all, fields(event_id))]
+async fn get_timeline<S: Service>(
+    State(service): State<S>,
+    Path(event_id): Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(service.get_timeline(event_id).await?.map_or_else(
+        || StatusCode::NOT_FOUND.into_response(),
+        |timeline| Json(timeline).into_response(),
+    ))
+}
+
+#[instrument(skip_all, fields(id))]
+async fn pin_item<S: Service>(
+    State(service): State<S>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(status(service.set_item_pinned(id, true).await?))
+}
+
+#[instrument(skip_all)]
+async fn reclassify_items<S: Service>(
+    State(service): State<S>,
+    Query(filter): Query<ItemFilter>,
+    Query(params): Query<ReclassifyParams>,
+) -> impl IntoResponse {
+    service
+        .reclassify_items(&filter, &params)
+        .await
+        .to_json_response()
+}
+
 async fn redirect_to_base(uri: Uri) -> impl IntoResponse {
     Redirect::permanent(&format!(
         "/sink{}",
@@ -180,6 +316,22 @@ async fn redirect_to_base(uri: Uri) -> impl IntoResponse {
     ))
 }
 
+#[instrument(skip_all, fields(id, note_id))]
+async fn remove_item_note<S: Service>(
+    State(service): State<S>,
+    Path((id, note_id)): Path<(i64, i64)>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(status(service.remove_item_note(id, note_id).await?))
+}
+
+#[instrument(skip_all, fields(id, tag))]
+async fn remove_item_tag<S: Service>(
+    State(service): State<S>,
+    Path((id, tag)): Path<(i64, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(status(service.remove_item_tag(id, &tag).await?))
+}
+
 fn respond_with_data(data: impl Serialize) -> Result<impl IntoResponse, AppError> {
     const INITIAL_DATA: &[u8] = b"'%INITIAL_DATA%'";
 
@@ -203,6 +355,18 @@ fn respond_with_data(data: impl Serialize) -> Result<impl IntoResponse, AppError
     Ok(response)
 }
 
+#[instrument(skip_all, fields(name))]
+async fn save_saved_search<S: Service>(
+    State(service): State<S>,
+    Path(name): Path<String>,
+    Json(search): Json<NewSavedSearch>,
+) -> impl IntoResponse {
+    service
+        .save_saved_search(&name, &search)
+        .await
+        .to_json_response()
+}
+
 pub async fn start<S>(host: &str, port: u16, service: S) -> Result<()>
 where
     S: Service + 'static,
@@ -221,12 +385,34 @@ where
                 .nest(
                     "/api",
                     Router::new()
+                        .route("/diff", get(diff_items::<S>))
+                        .route("/event-types", get(get_event_types::<S>))
                         .route("/item/{id}", get(get_item::<S>))
+                        .route("/item/{id}/notes", post(add_item_note::<S>))
+                        .route("/item/{id}/related", get(get_related_items::<S>))
+                        .route("/item/{id}/pin", put(pin_item::<S>).delete(unpin_item::<S>))
+                        .route("/item/{id}/notes/{note_id}", delete(remove_item_note::<S>))
+                        .route(
+                            "/item/{id}/tags/{tag}",
+                            put(add_item_tag::<S>).delete(remove_item_tag::<S>),
+                        )
+                        .route("/item-types", get(get_item_types::<S>))
                         .route("/items", get(get_items::<S>))
                         .route(
                             "/raw-item/{id}",
                             get(get_raw_item::<S>).post(get_raw_item::<S>),
-                        ),
+                        )
+                        .route("/reclassify", post(reclassify_items::<S>))
+                        .route("/saved-searches", get(get_saved_searches::<S>))
+                        .route(
+                            "/saved-searches/{name}",
+                            get(get_saved_search::<S>)
+                                .put(save_saved_search::<S>)
+                                .delete(delete_saved_search::<S>),
+                        )
+                        .route("/stats", get(get_stats::<S>))
+                        .route("/stats/histogram", get(get_histogram::<S>))
+                        .route("/timeline/{event_id}", get(get_timeline::<S>)),
                 )
                 .fallback(get(get_asset).post(submit_item::<S>)),
         )
@@ -248,9 +434,18 @@ where
     .map_err(Into::into)
 }
 
+fn status(found: bool) -> StatusCode {
+    if found {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
 #[instrument(skip_all)]
 async fn submit_item<S: Service>(
     State(service): State<S>,
+    OriginalUri(uri): OriginalUri,
     headers: HeaderMap,
     body: Bytes,
 ) -> impl IntoResponse {
@@ -262,7 +457,18 @@ async fn submit_item<S: Service>(
         })
         .collect();
 
-    service.save_item(&headers, &body).await.to_json_response()
+    service
+        .save_item(Some(uri.path()), &headers, &body)
+        .await
+        .to_json_response()
+}
+
+#[instrument(skip_all, fields(id))]
+async fn unpin_item<S: Service>(
+    State(service): State<S>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(status(service.set_item_pinned(id, false).await?))
 }
 
 fn trace_layer_make_span_with(request: &Request<Body>) -> Span {
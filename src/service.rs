@@ -1,27 +1,120 @@
-use std::{borrow::Cow, future::Future};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    future::Future,
+    path::PathBuf,
+};
 
 use crate::{
-    model::{Item, ItemFilter, ItemSearchResult, NewItem, NewItemHeader},
+    diff::{DiffIgnore, diff_items},
+    event_type::EventTypes,
+    field::FieldExtractors,
+    item_type::ItemTypes,
+    model::{
+        CountMode, DiffChange, DiffOp, DiffParams, EventTypeGroup, FacetCount, FacetValue,
+        HistogramCount, HistogramParams, Item, ItemClassification, ItemDiff, ItemFacets, ItemField,
+        ItemFilter, ItemNote, ItemPage, ItemRelationship, ItemSearchResult, ItemSummary, ItemType,
+        NewItem, NewItemHeader, NewItemNote, NewSavedSearch, NotFoundError, ReclassifiedItem,
+        ReclassifyParams, ReclassifyResult, RelatedItem, SavedSearch, Stats, Timeline,
+        ValidationError,
+    },
+    query::{parse_query, tokenize_query},
+    reload::Reloadable,
     repository::Repository,
+    schema::ItemSchemas,
+    system::SystemRules,
 };
 
 use anyhow::Result;
-use regex::bytes::{Regex, RegexSet};
+use regex::bytes::Regex;
 use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::runtime::Handle;
+use tracing::error;
+
+const RECLASSIFY_BATCH_SIZE: u32 = 100;
+const RECLASSIFY_ITEMS_LIMIT: usize = 100;
 
 pub trait Service: Clone + Send + Sync {
+    fn add_item_note(
+        &self,
+        item_id: i64,
+        note: &NewItemNote,
+    ) -> impl Future<Output = Result<Option<ItemNote>>> + Send;
+
+    fn add_item_tag(&self, item_id: i64, tag: &str) -> impl Future<Output = Result<bool>> + Send;
+    fn delete_saved_search(&self, name: &str) -> impl Future<Output = Result<bool>> + Send;
+    fn diff_items(&self, params: &DiffParams) -> impl Future<Output = Result<ItemDiff>> + Send;
+
+    fn get_event_types(&self) -> impl Future<Output = Result<Vec<EventTypeGroup>>> + Send;
+
+    fn get_histogram(
+        &self,
+        filter: &ItemFilter,
+        params: &HistogramParams,
+    ) -> impl Future<Output = Result<Vec<HistogramCount>>> + Send;
+
     fn get_item(&self, id: i64) -> impl Future<Output = Result<Option<Item>>> + Send;
+    fn get_item_types(&self) -> impl Future<Output = Result<Vec<ItemType>>> + Send;
 
     fn get_items(
         &self,
         filter: &ItemFilter,
     ) -> impl Future<Output = Result<ItemSearchResult>> + Send;
 
+    fn get_related_items(
+        &self,
+        id: i64,
+    ) -> impl Future<Output = Result<Option<Vec<RelatedItem>>>> + Send;
+
+    fn get_saved_search(
+        &self,
+        name: &str,
+    ) -> impl Future<Output = Result<Option<SavedSearch>>> + Send;
+
+    fn get_saved_searches(&self) -> impl Future<Output = Result<Vec<SavedSearch>>> + Send;
+    fn get_stats(&self) -> impl Future<Output = Result<Stats>> + Send;
+
+    fn get_timeline(&self, event_id: i64) -> impl Future<Output = Result<Option<Timeline>>> + Send;
+
+    fn reclassify_items(
+        &self,
+        filter: &ItemFilter,
+        params: &ReclassifyParams,
+    ) -> impl Future<Output = Result<ReclassifyResult>> + Send;
+
+    fn remove_item_note(
+        &self,
+        item_id: i64,
+        note_id: i64,
+    ) -> impl Future<Output = Result<bool>> + Send;
+
+    fn remove_item_tag(&self, item_id: i64, tag: &str)
+    -> impl Future<Output = Result<bool>> + Send;
+
     fn save_item(
         &self,
+        path: Option<&str>,
         headers: &[NewItemHeader<'_>],
         body: &[u8],
     ) -> impl Future<Output = Result<i64>> + Send;
+
+    fn save_saved_search(
+        &self,
+        name: &str,
+        search: &NewSavedSearch,
+    ) -> impl Future<Output = Result<SavedSearch>> + Send;
+
+    fn set_item_pinned(&self, id: i64, pinned: bool) -> impl Future<Output = Result<bool>> + Send;
+}
+
+#[derive(Debug, Default)]
+pub struct ServiceOptions {
+    pub event_types: Option<PathBuf>,
+    pub field_extractors: Option<PathBuf>,
+    pub item_schemas: Option<PathBuf>,
+    pub item_types: Option<PathBuf>,
+    pub system_rules: Option<PathBuf>,
 }
 
 #[derive(Clone)]
@@ -30,9 +123,11 @@ where
     R: Repository,
 {
     repository: R,
-    item_types: Vec<(String, usize)>,
-    item_type_regexes: RegexSet,
-    system_regex: Regex,
+    event_types: Reloadable<EventTypes>,
+    field_extractors: Reloadable<FieldExtractors>,
+    item_schemas: Reloadable<ItemSchemas>,
+    item_types: Reloadable<ItemTypes>,
+    system_rules: Reloadable<SystemRules>,
     entity_event_id_regex: Regex,
 }
 
@@ -40,12 +135,79 @@ impl<R> Service for ServiceImpl<R>
 where
     R: Repository,
 {
+    async fn add_item_note(&self, item_id: i64, note: &NewItemNote) -> Result<Option<ItemNote>> {
+        if note.text.trim().is_empty() {
+            return Err(ValidationError("note text must not be empty".into()).into());
+        }
+
+        self.repository.add_item_note(item_id, note).await
+    }
+
+    async fn add_item_tag(&self, item_id: i64, tag: &str) -> Result<bool> {
+        let tag = tag.trim();
+
+        if tag.is_empty() {
+            return Err(ValidationError("tag must not be empty".into()).into());
+        }
+
+        self.repository.add_item_tag(item_id, tag).await
+    }
+
+    async fn delete_saved_search(&self, name: &str) -> Result<bool> {
+        self.repository.delete_saved_search(name).await
+    }
+
+    async fn diff_items(&self, params: &DiffParams) -> Result<ItemDiff> {
+        let mut items = Vec::with_capacity(2);
+
+        for id in [params.left, params.right] {
+            items.push(
+                self.repository
+                    .get_item(id)
+                    .await?
+                    .ok_or_else(|| NotFoundError(format!("item {id} not found")))?,
+            );
+        }
+
+        Ok(diff_items(
+            &items[0],
+            &items[1],
+            &DiffIgnore::new(params.ignore.as_deref()),
+        ))
+    }
+
+    async fn get_event_types(&self) -> Result<Vec<EventTypeGroup>> {
+        Ok(self.event_types.get().groups().to_vec())
+    }
+
+    async fn get_histogram(
+        &self,
+        filter: &ItemFilter,
+        params: &HistogramParams,
+    ) -> Result<Vec<HistogramCount>> {
+        self.repository
+            .get_histogram(filter, params.bucket.unwrap_or_default(), params.split)
+            .await
+    }
+
     async fn get_item(&self, id: i64) -> Result<Option<Item>> {
         self.repository.get_item(id).await
     }
 
+    async fn get_item_types(&self) -> Result<Vec<ItemType>> {
+        Ok(self.item_types.get().types().to_vec())
+    }
+
     async fn get_items(&self, filter: &ItemFilter) -> Result<ItemSearchResult> {
-        let (items, total_items) = self.repository.get_items(filter).await?;
+        let filter = &*self.resolve_filter(filter).await?;
+
+        let ItemPage {
+            items,
+            total_items,
+            total_items_capped,
+            next,
+            prev,
+        } = self.repository.get_items(filter).await?;
 
         let first_item = if filter.load_first_item.unwrap_or_default()
             && let Some(first_item) = items.first()
@@ -55,41 +217,253 @@ where
             None
         };
 
+        let facets = if filter.facets.unwrap_or_default() {
+            Some(self.get_facets(filter).await?)
+        } else {
+            None
+        };
+
         let systems = self.repository.get_systems().await?;
 
         Ok(ItemSearchResult {
             items,
             total_items,
+            total_items_capped,
             systems,
             first_item,
+            facets,
+            next,
+            prev,
         })
     }
 
-    async fn save_item(&self, headers: &[NewItemHeader<'_>], body: &[u8]) -> Result<i64> {
-        let system = self.get_system(headers, body);
-        let r#type = self.get_item_type(body);
+    async fn get_related_items(&self, id: i64) -> Result<Option<Vec<RelatedItem>>> {
+        let items = self.repository.get_related_items(id).await?;
+
+        if items.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(relate_items(items)))
+        }
+    }
+
+    async fn get_saved_search(&self, name: &str) -> Result<Option<SavedSearch>> {
+        self.repository.get_saved_search(name).await
+    }
+
+    async fn get_saved_searches(&self) -> Result<Vec<SavedSearch>> {
+        self.repository.get_saved_searches().await
+    }
+
+    async fn get_stats(&self) -> Result<Stats> {
+        self.repository.get_stats().await
+    }
+
+    async fn get_timeline(&self, event_id: i64) -> Result<Option<Timeline>> {
+        let event_types = self.event_types.get();
+        let mut steps = self.repository.get_timeline(event_id).await?;
+
+        if steps.is_empty() {
+            return Ok(None);
+        }
+
+        let mut seen = HashSet::new();
+
+        for step in &mut steps {
+            step.relationship = item_relationship(&mut seen, &step.summary);
+        }
+
+        let missing = [
+            ItemRelationship::Notification,
+            ItemRelationship::Payload,
+            ItemRelationship::Acknowledgement,
+        ]
+        .into_iter()
+        .filter(|relationship| !steps.iter().any(|step| step.relationship == *relationship))
+        .collect();
+
+        let entity_event_id = steps.iter().find_map(|step| step.summary.entity_event_id);
+
+        let (event_group, event_type) = entity_event_id
+            .and_then(|entity_event_id| event_types.get(entity_event_id))
+            .map(|(group, name)| (group.into(), name.into()))
+            .unzip();
+
+        Ok(Some(Timeline {
+            event_id,
+            entity_event_id,
+            event_type,
+            event_group,
+            steps,
+            missing,
+        }))
+    }
+
+    async fn reclassify_items(
+        &self,
+        filter: &ItemFilter,
+        params: &ReclassifyParams,
+    ) -> Result<ReclassifyResult> {
+        let dry_run = params.dry_run.unwrap_or_default();
+
+        if filter.batch_size == Some(0) {
+            return Err(ValidationError("batch size must be at least 1".into()).into());
+        }
+
+        let mut filter = ItemFilter {
+            asc: Some(true),
+            batch_size: Some(filter.batch_size.unwrap_or(RECLASSIFY_BATCH_SIZE)),
+            count: Some(CountMode::None),
+            facets: None,
+            load_first_item: None,
+            sort: None,
+            ..self.resolve_filter(filter).await?.into_owned()
+        };
+
+        let mut result = ReclassifyResult {
+            dry_run,
+            processed: 0,
+            changed: 0,
+            items: Vec::new(),
+        };
+
+        loop {
+            let page = self.repository.get_items(&filter).await?;
+
+            for summary in page.items {
+                let Some(item) = self.repository.get_item(summary.id).await? else {
+                    continue;
+                };
+
+                let headers: Vec<_> = item
+                    .headers
+                    .iter()
+                    .map(|header| NewItemHeader {
+                        name: &header.name,
+                        value: &header.value,
+                    })
+                    .collect();
+
+                let classification =
+                    self.classify(item.summary.path.as_deref(), &headers, &item.body);
+
+                let changes = classification_changes(&item, &classification);
+
+                result.processed += 1;
+
+                if changes.is_empty() {
+                    continue;
+                }
+
+                if !dry_run {
+                    self.repository
+                        .set_item_classification(item.summary.id, &classification)
+                        .await?;
+                }
+
+                result.changed += 1;
+
+                if result.items.len() < RECLASSIFY_ITEMS_LIMIT {
+                    result.items.push(ReclassifiedItem {
+                        id: item.summary.id,
+                        changes,
+                    });
+                }
+            }
+
+            let Some(next) = page.next else {
+                break;
+            };
+
+            filter.after_item_id = None;
+            filter.cursor = Some(next);
+        }
+
+        Ok(result)
+    }
+
+    async fn remove_item_note(&self, item_id: i64, note_id: i64) -> Result<bool> {
+        self.repository.remove_item_note(item_id, note_id).await
+    }
+
+    async fn remove_item_tag(&self, item_id: i64, tag: &str) -> Result<bool> {
+        self.repository.remove_item_tag(item_id, tag.trim()).await
+    }
+
+    async fn save_item(
+        &self,
+        path: Option<&str>,
+        headers: &[NewItemHeader<'_>],
+        body: &[u8],
+    ) -> Result<i64> {
+        let ItemClassification {
+            system,
+            r#type,
+            entity_event_id,
+            fields,
+            valid,
+            validation_errors,
+        } = self.classify(path, headers, body);
+
         let event_id = get_event_id(headers);
-        let entity_event_id = self.get_entity_event_id(body);
         let user_agent = get_user_agent(headers);
 
         self.repository
             .insert_item(&NewItem {
                 system: system.as_deref(),
-                r#type,
+                r#type: r#type.as_deref(),
                 event_id,
                 entity_event_id,
                 user_agent: user_agent.as_deref(),
+                path,
                 headers,
+                fields: &fields,
+                valid,
+                validation_errors: &validation_errors,
                 body,
             })
             .await
     }
+
+    async fn save_saved_search(&self, name: &str, search: &NewSavedSearch) -> Result<SavedSearch> {
+        validate_saved_search(name, search)?;
+
+        self.repository.save_saved_search(name, search).await?;
+
+        self.repository
+            .get_saved_search(name)
+            .await?
+            .ok_or_else(|| NotFoundError(format!("saved search '{name}' not found")).into())
+    }
+
+    async fn set_item_pinned(&self, id: i64, pinned: bool) -> Result<bool> {
+        self.repository.set_item_pinned(id, pinned).await
+    }
 }
 
 impl<R> ServiceImpl<R>
 where
     R: Repository,
 {
+    fn classify(
+        &self,
+        path: Option<&str>,
+        headers: &[NewItemHeader<'_>],
+        body: &[u8],
+    ) -> ItemClassification {
+        let r#type = self.get_item_type(path, headers, body);
+        let validation_errors = self.item_schemas.get().validate(r#type.as_deref(), body);
+
+        ItemClassification {
+            system: self.get_system(path, headers, body),
+            entity_event_id: self.get_entity_event_id(body),
+            fields: self.field_extractors.get().extract(body),
+            valid: validation_errors.as_ref().map(Vec::is_empty),
+            validation_errors: validation_errors.unwrap_or_default(),
+            r#type,
+        }
+    }
+
     fn get_entity_event_id(&self, body: &[u8]) -> Option<i64> {
         self.entity_event_id_regex
             .captures(body)
@@ -101,73 +475,229 @@ where
             })
     }
 
-    fn get_item_type(&self, body: &[u8]) -> Option<&str> {
-        let matches = self.item_type_regexes.matches(body);
+    async fn get_facets(&self, filter: &ItemFilter) -> Result<ItemFacets> {
+        let event_types = self.event_types.get();
+        let mut facets = ItemFacets::default();
+        let mut event_groups = HashMap::<Option<&str>, i32>::new();
 
-        if matches.matched_any() {
-            let mut i = 0;
-
-            'next_item_type: for (key, patterns) in &self.item_types {
-                for j in 0..*patterns {
-                    if !matches.matched(i + j) {
-                        i += patterns;
-                        continue 'next_item_type;
-                    }
+        for FacetValue {
+            facet,
+            value,
+            count,
+        } in self.repository.get_facets(filter).await?
+        {
+            let facet = match facet.as_str() {
+                "entityEventId" => {
+                    let group = value
+                        .and_then(|value| value.parse().ok())
+                        .and_then(|id| event_types.get(id))
+                        .map(|(group, _)| group);
+
+                    *event_groups.entry(group).or_default() += count;
+                    continue;
                 }
+                "system" => &mut facets.system,
+                "type" => &mut facets.r#type,
+                "userAgent" => &mut facets.user_agent,
+                _ => continue,
+            };
 
-                return Some(key);
-            }
+            facet.push(FacetCount { value, count });
         }
 
-        None
+        facets.event_group = event_groups
+            .into_iter()
+            .map(|(value, count)| FacetCount {
+                value: value.map(Into::into),
+                count,
+            })
+            .collect();
+
+        facets
+            .event_group
+            .sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+
+        Ok(facets)
     }
 
-    fn get_system<'a>(
+    fn get_item_type(
         &self,
-        headers: &[NewItemHeader<'a>],
-        body: &'a [u8],
-    ) -> Option<Cow<'a, str>> {
-        headers
-            .iter()
-            .find(|header| header.name == "mgs-system-id" || header.name == "mgssystem")
-            .map(|header| header.value)
-            .or_else(|| {
-                self.system_regex
-                    .captures(body)
-                    .and_then(|captures| captures.get(1))
-                    .map(|group| group.as_bytes())
-            })
-            .map(|system| String::from_utf8_lossy(system))
+        path: Option<&str>,
+        headers: &[NewItemHeader<'_>],
+        body: &[u8],
+    ) -> Option<String> {
+        self.item_types
+            .get()
+            .get_item_type(path, headers, body)
+            .map(Into::into)
     }
 
-    fn new(repository: R) -> Result<Self> {
-        #[derive(Deserialize)]
-        struct ItemType {
-            key: String,
-            matches: Vec<String>,
-        }
+    fn get_system(
+        &self,
+        path: Option<&str>,
+        headers: &[NewItemHeader<'_>],
+        body: &[u8],
+    ) -> Option<String> {
+        self.system_rules.get().get_system(path, headers, body)
+    }
 
-        let item_types: Vec<ItemType> = serde_json::from_str(include_str!("../item.types.json"))?;
+    async fn resolve_filter<'a>(&self, filter: &'a ItemFilter) -> Result<Cow<'a, ItemFilter>> {
+        let Some(name) = &filter.saved else {
+            return Ok(Cow::Borrowed(filter));
+        };
+
+        let search = self
+            .repository
+            .get_saved_search(name)
+            .await?
+            .ok_or_else(|| NotFoundError(format!("saved search '{name}' not found")))?;
+
+        Ok(Cow::Owned(
+            filter.clone().or(ItemFilter::deserialize(&search.filter)?),
+        ))
+    }
 
-        let item_type_regexes = RegexSet::new(
-            item_types
-                .iter()
-                .flat_map(|item_type| item_type.matches.iter()),
-        )?;
+    fn new(repository: R, options: ServiceOptions) -> Result<Self> {
+        let event_types = if let Some(path) = options.event_types {
+            let repository = repository.clone();
+            let runtime = Handle::try_current()?;
+
+            Reloadable::load_with(path, EventTypes::parse, move |event_types| {
+                let repository = repository.clone();
+
+                runtime.spawn(async move {
+                    if let Err(e) = repository.set_event_types(event_types.groups()).await {
+                        error!("event type sync failed: {e:#}");
+                    }
+                });
+            })?
+        } else {
+            Reloadable::new(EventTypes::builtin()?)
+        };
+
+        let field_extractors = if let Some(path) = options.field_extractors {
+            Reloadable::load(path, FieldExtractors::parse)?
+        } else {
+            Reloadable::new(FieldExtractors::builtin()?)
+        };
+
+        let item_schemas = if let Some(path) = options.item_schemas {
+            Reloadable::load(path, ItemSchemas::parse)?
+        } else {
+            Reloadable::new(ItemSchemas::builtin()?)
+        };
+
+        let item_types = if let Some(path) = options.item_types {
+            Reloadable::load(path, ItemTypes::parse)?
+        } else {
+            Reloadable::new(ItemTypes::builtin()?)
+        };
+
+        let system_rules = if let Some(path) = options.system_rules {
+            Reloadable::load(path, SystemRules::parse)?
+        } else {
+            Reloadable::new(SystemRules::builtin()?)
+        };
 
         Ok(Self {
             repository,
-            item_types: item_types
-                .into_iter()
-                .map(|item_type| (item_type.key, item_type.matches.len()))
-                .collect(),
-            item_type_regexes,
-            system_regex: Regex::new("<mgsSystem>([^<]+)")?,
+            event_types,
+            field_extractors,
+            item_schemas,
+            item_types,
+            system_rules,
             entity_event_id_regex: Regex::new(r#""entityEventId"\s*:\s*(\d+)"#)?,
         })
     }
 }
 
+fn classification_changes(item: &Item, classification: &ItemClassification) -> Vec<DiffChange> {
+    fn change(path: &str, left: Value, right: Value) -> DiffChange {
+        let value = |value: Value| {
+            Some(value).filter(|value| {
+                !value.is_null() && value.as_array().is_none_or(|array| !array.is_empty())
+            })
+        };
+
+        let (left, right) = (value(left), value(right));
+
+        DiffChange {
+            path: path.into(),
+            op: match (&left, &right) {
+                (None, _) => DiffOp::Added,
+                (_, None) => DiffOp::Removed,
+                _ => DiffOp::Changed,
+            },
+            left,
+            right,
+        }
+    }
+
+    fn sorted(fields: &[ItemField]) -> Vec<(String, &str)> {
+        let mut fields: Vec<_> = fields
+            .iter()
+            .map(|field| (field.name.to_lowercase(), field.value.as_str()))
+            .collect();
+
+        fields.sort_unstable();
+        fields.dedup();
+        fields
+    }
+
+    let summary = &item.summary;
+    let mut changes = Vec::new();
+
+    if summary.system != classification.system {
+        changes.push(change(
+            "system",
+            json!(summary.system),
+            json!(classification.system),
+        ));
+    }
+
+    if summary.r#type != classification.r#type {
+        changes.push(change(
+            "type",
+            json!(summary.r#type),
+            json!(classification.r#type),
+        ));
+    }
+
+    if summary.entity_event_id != classification.entity_event_id {
+        changes.push(change(
+            "entityEventId",
+            json!(summary.entity_event_id),
+            json!(classification.entity_event_id),
+        ));
+    }
+
+    if sorted(&item.fields) != sorted(&classification.fields) {
+        changes.push(change(
+            "fields",
+            json!(item.fields),
+            json!(classification.fields),
+        ));
+    }
+
+    if summary.valid != classification.valid {
+        changes.push(change(
+            "valid",
+            json!(summary.valid),
+            json!(classification.valid),
+        ));
+    }
+
+    if item.validation_errors != classification.validation_errors {
+        changes.push(change(
+            "validationErrors",
+            json!(item.validation_errors),
+            json!(classification.validation_errors),
+        ));
+    }
+
+    changes
+}
+
 fn get_event_id(headers: &[NewItemHeader<'_>]) -> Option<i64> {
     headers
         .iter()
@@ -182,8 +712,79 @@ fn get_user_agent<'a>(headers: &[NewItemHeader<'a>]) -> Option<Cow<'a, str>> {
         .map(|header| String::from_utf8_lossy(header.value))
 }
 
-pub fn new_service(repository: impl Repository) -> Result<impl Service> {
-    ServiceImpl::new(repository)
+fn item_relationship(
+    seen: &mut HashSet<(Option<String>, Option<i64>, Option<i64>)>,
+    summary: &ItemSummary,
+) -> ItemRelationship {
+    let is_first = seen.insert((
+        summary.r#type.clone(),
+        summary.event_id,
+        summary.entity_event_id,
+    ));
+
+    match summary.r#type.as_deref() {
+        Some("event_notification" | "event_payload") if !is_first => ItemRelationship::Redelivery,
+        Some("event_notification") => ItemRelationship::Notification,
+        Some("event_payload") => ItemRelationship::Payload,
+        _ if summary.system.is_some() => ItemRelationship::Acknowledgement,
+        _ => ItemRelationship::Other,
+    }
+}
+
+fn relate_items(items: Vec<ItemSummary>) -> Vec<RelatedItem> {
+    let mut seen = HashSet::new();
+
+    items
+        .into_iter()
+        .map(|summary| RelatedItem {
+            relationship: item_relationship(&mut seen, &summary),
+            summary,
+        })
+        .collect()
+}
+
+fn validate_saved_search(name: &str, search: &NewSavedSearch) -> Result<()> {
+    if name.trim().is_empty() {
+        return Err(ValidationError("saved search name must not be empty".into()).into());
+    }
+
+    if !search.filter.is_object() {
+        return Err(ValidationError("saved search filter must be an object".into()).into());
+    }
+
+    let filter = ItemFilter::deserialize(&search.filter)
+        .map_err(|e| ValidationError(format!("invalid saved search filter: {e}")))?;
+
+    if filter.saved.is_some() {
+        return Err(ValidationError(
+            "saved search filter must not reference another saved search".into(),
+        )
+        .into());
+    }
+
+    if let Some(query) = &filter.query {
+        parse_query(&tokenize_query(query))?;
+    }
+
+    Ok(())
+}
+
+pub async fn new_service(repository: impl Repository) -> Result<impl Service> {
+    new_service_with_options(repository, ServiceOptions::default()).await
+}
+
+pub async fn new_service_with_options(
+    repository: impl Repository,
+    options: ServiceOptions,
+) -> Result<impl Service> {
+    let service = ServiceImpl::new(repository, options)?;
+
+    service
+        .repository
+        .set_event_types(service.event_types.get().groups())
+        .await?;
+
+    Ok(service)
 }
 
 #[cfg(test)]
@@ -200,7 +801,7 @@ mod tests {
 
     #[fixture]
     fn service(repository: impl Repository) -> ServiceImpl<impl Repository> {
-        ServiceImpl::new(repository).unwrap()
+        ServiceImpl::new(repository, ServiceOptions::default()).unwrap()
     }
 
     #[rstest]
@@ -258,8 +859,8 @@ mod tests {
         #[case] expected_item_type: Option<&str>,
         service: ServiceImpl<impl Repository>,
     ) -> Result<()> {
-        let item_type = service.get_item_type(body);
-        assert_eq!(item_type, expected_item_type);
+        let item_type = service.get_item_type(None, &[], body);
+        assert_eq!(item_type.as_deref(), expected_item_type);
         Ok(())
     }
 
@@ -276,6 +877,7 @@ mod tests {
         service: ServiceImpl<impl Repository>,
     ) -> Result<()> {
         let system = service.get_system(
+            None,
             &[NewItemHeader {
                 name: header_name,
                 value: header_value,
@@ -287,4 +889,44 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_relate_items() {
+        let item = |id, r#type: Option<&str>, event_id, entity_event_id| ItemSummary {
+            id,
+            r#type: r#type.map(Into::into),
+            event_id,
+            entity_event_id,
+            ..Default::default()
+        };
+
+        let relationships: Vec<(i64, ItemRelationship)> = relate_items(vec![
+            item(1, Some("event_notification"), Some(10), Some(20)),
+            item(2, Some("event_payload"), None, Some(20)),
+            item(3, Some("event_notification"), Some(10), Some(20)),
+            item(4, Some("event_notification"), Some(11), Some(20)),
+            item(5, Some("status_updated"), None, Some(20)),
+            item(6, None, Some(10), None),
+            ItemSummary {
+                system: Some("system".into()),
+                ..item(7, Some("status_updated"), Some(10), None)
+            },
+        ])
+        .into_iter()
+        .map(|item| (item.summary.id, item.relationship))
+        .collect();
+
+        assert_eq!(
+            relationships,
+            &[
+                (1, ItemRelationship::Notification),
+                (2, ItemRelationship::Payload),
+                (3, ItemRelationship::Redelivery),
+                (4, ItemRelationship::Notification),
+                (5, ItemRelationship::Other),
+                (6, ItemRelationship::Other),
+                (7, ItemRelationship::Acknowledgement),
+            ]
+        );
+    }
 }
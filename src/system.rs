@@ -0,0 +1,217 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use anyhow::Result;
+use regex::bytes::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json_path::JsonPath;
+
+use crate::model::NewItemHeader;
+
+pub struct SystemRules {
+    rules: Vec<SystemRule>,
+    aliases: HashMap<String, String>,
+}
+
+impl SystemRules {
+    pub fn builtin() -> Result<Self> {
+        Self::parse(include_str!("../system.rules.json"))
+    }
+
+    pub fn get_system(
+        &self,
+        path: Option<&str>,
+        headers: &[NewItemHeader<'_>],
+        body: &[u8],
+    ) -> Option<String> {
+        let mut json = None;
+
+        let system = self.rules.iter().find_map(|rule| {
+            let system = match rule {
+                SystemRule::Body(regex) => capture(regex, body),
+                SystemRule::Default(system) => Some(Cow::Borrowed(system.as_str())),
+                SystemRule::Header(name, regex) => headers
+                    .iter()
+                    .filter(|header| header.name.eq_ignore_ascii_case(name))
+                    .find_map(|header| match regex {
+                        Some(regex) => capture(regex, header.value),
+                        None => Some(String::from_utf8_lossy(header.value)),
+                    }),
+                SystemRule::JsonPath(json_path) => json
+                    .get_or_insert_with(|| serde_json::from_slice::<Value>(body).ok())
+                    .as_ref()
+                    .and_then(|json| json_path.query(json).first())
+                    .and_then(|value| match value {
+                        Value::String(text) => Some(Cow::Owned(text.clone())),
+                        Value::Number(number) => Some(Cow::Owned(number.to_string())),
+                        _ => None,
+                    }),
+                SystemRule::PathSegment(index) => path
+                    .and_then(|path| {
+                        path.split('/')
+                            .filter(|segment| !segment.is_empty())
+                            .nth(*index)
+                    })
+                    .map(Cow::Borrowed),
+                SystemRule::UserAgent(regex) => headers
+                    .iter()
+                    .find(|header| header.name.eq_ignore_ascii_case("user-agent"))
+                    .and_then(|header| match regex {
+                        Some(regex) => capture(regex, header.value),
+                        None => Some(String::from_utf8_lossy(header.value)),
+                    }),
+            };
+
+            system.filter(|system| !system.trim().is_empty())
+        })?;
+
+        let system = system.trim();
+
+        Some(
+            self.aliases
+                .get(&system.to_lowercase())
+                .cloned()
+                .unwrap_or_else(|| system.into()),
+        )
+    }
+
+    pub fn parse(json: &str) -> Result<Self> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase", tag = "source")]
+        enum SystemRuleConfig {
+            Body { regex: String },
+            Default { system: String },
+            Header { name: String, regex: Option<String> },
+            JsonPath { path: String },
+            PathSegment { index: usize },
+            UserAgent { regex: Option<String> },
+        }
+
+        #[derive(Deserialize)]
+        struct SystemRulesConfig {
+            rules: Vec<SystemRuleConfig>,
+            #[serde(default)]
+            aliases: HashMap<String, String>,
+        }
+
+        let config: SystemRulesConfig = serde_json::from_str(json)?;
+
+        let regex = |pattern: Option<String>| pattern.as_deref().map(Regex::new).transpose();
+
+        let rules = config
+            .rules
+            .into_iter()
+            .map(|rule| {
+                Ok(match rule {
+                    SystemRuleConfig::Body { regex } => SystemRule::Body(Regex::new(&regex)?),
+                    SystemRuleConfig::Default { system } => SystemRule::Default(system),
+                    SystemRuleConfig::Header {
+                        name,
+                        regex: pattern,
+                    } => SystemRule::Header(name, regex(pattern)?),
+                    SystemRuleConfig::JsonPath { path } => {
+                        SystemRule::JsonPath(JsonPath::parse(&path)?)
+                    }
+                    SystemRuleConfig::PathSegment { index } => SystemRule::PathSegment(index),
+                    SystemRuleConfig::UserAgent { regex: pattern } => {
+                        SystemRule::UserAgent(regex(pattern)?)
+                    }
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            rules,
+            aliases: config
+                .aliases
+                .into_iter()
+                .map(|(alias, system)| (alias.to_lowercase(), system))
+                .collect(),
+        })
+    }
+}
+
+enum SystemRule {
+    Body(Regex),
+    Default(String),
+    Header(String, Option<Regex>),
+    JsonPath(JsonPath),
+    PathSegment(usize),
+    UserAgent(Option<Regex>),
+}
+
+fn capture<'a>(regex: &Regex, text: &'a [u8]) -> Option<Cow<'a, str>> {
+    regex
+        .captures(text)
+        .and_then(|captures| captures.get(1).or_else(|| captures.get(0)))
+        .map(|group| String::from_utf8_lossy(group.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("", false)]
+    #[case("[]", false)]
+    #[case(r#"{"rules": []}"#, true)]
+    #[case(r#"{"rules": [{"source": "unknown"}]}"#, false)]
+    #[case(r#"{"rules": [{"source": "body", "regex": "("}]}"#, false)]
+    #[case(r#"{"rules": [{"source": "jsonPath", "path": "x"}]}"#, false)]
+    #[case(
+        r#"{"rules": [{"source": "pathSegment", "index": 1}], "aliases": {"a": "b"}}"#,
+        true
+    )]
+    fn test_parse(#[case] json: &str, #[case] expected_valid: bool) {
+        assert_eq!(SystemRules::parse(json).is_ok(), expected_valid);
+    }
+
+    #[rstest]
+    #[case(None, &[], b"", Some("listener"))]
+    #[case(None, &[("X-System", " acme ")], b"", Some("acme"))]
+    #[case(None, &[("x-ssl-client-s-dn", "O=Org,CN=Partner,C=US")], b"", Some("partner"))]
+    #[case(None, &[], b"<mgsSystem>mgs</mgsSystem>", Some("MGS"))]
+    #[case(None, &[], br#"{"sourceSystem": "json"}"#, Some("json"))]
+    #[case(None, &[], br#"{"sourceSystem": 42}"#, Some("42"))]
+    #[case(None, &[("user-agent", "agent/1.0")], b"", Some("agent"))]
+    #[case(Some("/sink/path/events"), &[], b"", Some("path"))]
+    #[case(Some("/sink"), &[], b"", Some("listener"))]
+    #[case(None, &[("x-system", " ")], b"", Some("listener"))]
+    fn test_get_system(
+        #[case] path: Option<&str>,
+        #[case] headers: &[(&str, &str)],
+        #[case] body: &[u8],
+        #[case] expected_system: Option<&str>,
+    ) -> Result<()> {
+        let rules = SystemRules::parse(
+            r#"{
+                "rules": [
+                    {"source": "header", "name": "x-system"},
+                    {"source": "header", "name": "x-ssl-client-s-dn", "regex": "CN=([^,]+)"},
+                    {"source": "body", "regex": "<mgsSystem>([^<]+)"},
+                    {"source": "jsonPath", "path": "$.sourceSystem"},
+                    {"source": "userAgent", "regex": "^[^/]+"},
+                    {"source": "pathSegment", "index": 1},
+                    {"source": "default", "system": "listener"}
+                ],
+                "aliases": {"Partner": "partner", "mgs": "MGS"}
+            }"#,
+        )?;
+
+        let headers: Vec<_> = headers
+            .iter()
+            .map(|(name, value)| NewItemHeader {
+                name,
+                value: value.as_bytes(),
+            })
+            .collect();
+
+        assert_eq!(
+            rules.get_system(path, &headers, body).as_deref(),
+            expected_system
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use sxd_document::{
+    Package,
+    dom::{ChildOfElement, ChildOfRoot, Element},
+    parser,
+};
+use sxd_xpath::{Error, Factory, XPath};
+
+const XPATH_CACHE_SIZE: usize = 64;
+
+// Compiled XPaths are neither Send nor Sync, so they can't be kept in SQLite
+// function aux data (as json_matches does with JSONPaths) or shared between
+// field extractor threads. Each thread, i.e. each SQLite connection worker or
+// ingest task thread, keeps its own small cache that is dropped once full.
+thread_local! {
+    static XPATHS: RefCell<HashMap<String, Option<XPath>>> = RefCell::new(HashMap::new());
+}
+
+pub fn parse(text: &str) -> Option<Package> {
+    let package = parser::parse(text).ok()?;
+
+    for child in package.as_document().root().children() {
+        if let ChildOfRoot::Element(element) = child {
+            strip_namespaces(element);
+        }
+    }
+
+    Some(package)
+}
+
+pub fn with_xpath<T>(path: &str, f: impl FnOnce(Option<&XPath>) -> T) -> Result<T, Error> {
+    XPATHS.with_borrow_mut(|xpaths| {
+        if !xpaths.contains_key(path) {
+            let xpath = Factory::new().build(path)?;
+
+            if xpaths.len() >= XPATH_CACHE_SIZE {
+                xpaths.clear();
+            }
+
+            xpaths.insert(path.into(), xpath);
+        }
+
+        Ok(f(xpaths[path].as_ref()))
+    })
+}
+
+fn strip_namespaces(element: Element<'_>) {
+    let mut elements = vec![element];
+
+    while let Some(element) = elements.pop() {
+        element.set_name(element.name().local_part());
+        element.set_default_namespace_uri(None);
+
+        for attribute in element.attributes() {
+            let name = attribute.name();
+
+            if name.namespace_uri().is_some() {
+                element.remove_attribute(name);
+                element.set_attribute_value(name.local_part(), attribute.value());
+            }
+        }
+
+        for child in element.children() {
+            if let ChildOfElement::Element(child) = child {
+                elements.push(child);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use sxd_xpath::Context;
+
+    #[rstest]
+    #[case("<a/>", "name(/*)", Some("a"))]
+    #[case(
+        r#"<t:a xmlns:t="urn:t"><t:b>1</t:b></t:a>"#,
+        "string(/a/b)",
+        Some("1")
+    )]
+    #[case(
+        r#"<a xmlns="urn:t" xmlns:t="urn:t" t:b="2"/>"#,
+        "string(/a/@b)",
+        Some("2")
+    )]
+    #[case("<a>", "/a", None)]
+    fn test_parse(#[case] text: &str, #[case] path: &str, #[case] expected: Option<&str>) {
+        let value = parse(text).map(|package| {
+            let document = package.as_document();
+
+            with_xpath(path, |xpath| {
+                xpath
+                    .unwrap()
+                    .evaluate(&Context::new(), document.root())
+                    .map(|value| value.string())
+            })
+            .unwrap()
+            .unwrap()
+        });
+
+        assert_eq!(value.as_deref(), expected);
+    }
+
+    #[test]
+    fn test_parse_deeply_nested() {
+        const LEVELS: usize = 10_000;
+
+        let text = format!(
+            r#"{}<t:b xmlns:t="urn:t">1</t:b>{}"#,
+            "<a>".repeat(LEVELS),
+            "</a>".repeat(LEVELS)
+        );
+
+        let package = parse(&text).unwrap();
+        let mut element = package.as_document().root().children()[0].element();
+
+        while let Some(child) = element.and_then(|element| element.children()[0].element()) {
+            element = Some(child);
+        }
+
+        assert_eq!(element.unwrap().name().local_part(), "b");
+        assert_eq!(element.unwrap().name().namespace_uri(), None);
+    }
+
+    #[rstest]
+    #[case("//a", Some(true))]
+    #[case("", Some(false))]
+    #[case("//[", None)]
+    fn test_with_xpath(#[case] path: &str, #[case] expected: Option<bool>) {
+        for _ in 0..2 {
+            assert_eq!(with_xpath(path, |xpath| xpath.is_some()).ok(), expected);
+        }
+    }
+}
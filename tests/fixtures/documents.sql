@@ -0,0 +1,22 @@
+INSERT INTO item (id, type, submit_date) VALUES(1, 'event_payload', '2025-01-01');
+INSERT INTO item_header (item_id, name, value) VALUES (1, 'content-type', CAST('application/json' AS BLOB));
+INSERT INTO item_body (item_id, body) VALUES (1, CAST('{"entity": {"vacancyId": 42}, "eventDesc": "Applicant Hired"}' AS BLOB));
+
+INSERT INTO item (id, type, submit_date) VALUES(2, 'event_payload', '2025-01-02');
+INSERT INTO item_header (item_id, name, value) VALUES (2, 'content-type', CAST('application/json; charset=utf-8' AS BLOB));
+INSERT INTO item_body (item_id, body) VALUES (2, CAST('{"entity":{"vacancyId":"43"},"eventDesc":"Vacancy Updated","tags":["a","b"]}' AS BLOB));
+
+INSERT INTO item (id, type, submit_date) VALUES(3, 'vacancy_updated', '2025-01-03');
+INSERT INTO item_header (item_id, name, value) VALUES (3, 'content-type', CAST('text/xml' AS BLOB));
+INSERT INTO item_body (item_id, body) VALUES (3, CAST('<tns:updatedVacancy xmlns:tns="urn:vacancy"><tns:vacancyId>42</tns:vacancyId></tns:updatedVacancy>' AS BLOB));
+
+INSERT INTO item (id, submit_date) VALUES(4, '2025-01-04');
+INSERT INTO item_body (item_id, body) VALUES (4, CAST('{"entity": {"vacancyId": 42}}' AS BLOB));
+
+INSERT INTO item (id, submit_date) VALUES(5, '2025-01-05');
+INSERT INTO item_header (item_id, name, value) VALUES (5, 'content-type', CAST('application/json' AS BLOB));
+INSERT INTO item_body (item_id, body) VALUES (5, CAST('not json' AS BLOB));
+
+INSERT INTO item (id, type, submit_date) VALUES(6, 'folder_cl', '2025-01-06');
+INSERT INTO item_header (item_id, name, value) VALUES (6, 'content-type', CAST('application/soap+xml' AS BLOB));
+INSERT INTO item_body (item_id, body) VALUES (6, CAST('<ns1:Folders xmlns:ns1="urn:pc" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><ns1:Folder type="CL" id="123" xsi:nil="false"/><ns1:Folder type="FS" id="456"/></ns1:Folders>' AS BLOB));
@@ -3,7 +3,7 @@ INSERT INTO item_header (item_id, name, value) VALUES (1, 'header-1', X'76616C75
 INSERT INTO item_header (item_id, name, value) VALUES (1, 'header-2', X'76616C75652D32'); -- value-2
 INSERT INTO item_body (item_id, body) VALUES (1, X'787878626F64792D31787878'); -- xxxbody-1xxx
 
-INSERT INTO item (id, system, type, submit_date) VALUES(2, 'system-1', 'type-2', '2025-01-02');
+INSERT INTO item (id, system, type, user_agent, submit_date) VALUES(2, 'system-1', 'type-2', 'agent/1.0', '2025-01-02');
 INSERT INTO item_header (item_id, name, value) VALUES (2, 'header-1', X'76616C75652D31'); -- value-1
 INSERT INTO item_body (item_id, body) VALUES (2, X'787878626F64792D32787878'); -- xxxbody-2xxx
 
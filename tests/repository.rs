@@ -1,9 +1,15 @@
 use anyhow::Result;
 use axum::{extract::Query, http::Uri};
 use rstest::rstest;
+use serde_json::json;
 
 use sink::{
-    model::{ItemFilter, NewItem, NewItemHeader},
+    cursor::CursorError,
+    model::{
+        EventType, EventTypeGroup, FacetValue, HistogramBucket, HistogramCount, HistogramSplit,
+        ItemClassification, ItemField, ItemFilter, ItemPage, ItemValidationError, NewItem,
+        NewItemHeader, NewItemNote, NewSavedSearch, UserAgentStats,
+    },
     repository::{Repository, register_match_function},
 };
 
@@ -28,6 +34,52 @@ async fn test_get_item_nonexistent(repository: SqlitePool) -> Result<()> {
 #[case("query=body:id:5", &[3], 1)]
 #[case("query=regex:body-[12]", &[2, 1], 2)]
 #[case("query=body-1", &[1], 1)]
+#[case("query=id:1%20OR%20id:3", &[3, 1], 2)]
+#[case("query=-header-1:value-1", &[5, 4, 3], 3)]
+#[case("query=NOT%20(id:1%20OR%20id:2)%20regex:.", &[4, 3], 2)]
+#[case("query=(event-id:1%20OR%20body:id)%20-body-1", &[3], 1)]
+#[case("query=id:2..4", &[4, 3, 2], 3)]
+#[case("query=id:%3E3", &[5, 4], 2)]
+#[case("query=id:%3C=2", &[2, 1], 2)]
+#[case("query=event-id:%3E=1", &[1], 1)]
+#[case("query=size:%3E12", &[4], 1)]
+#[case("query=size:%3C1", &[5], 1)]
+#[case("query=size:4..12", &[3, 2, 1], 3)]
+#[case("query=date:2025-01-02", &[2], 1)]
+#[case("query=date:2025-01-02..2025-01-04", &[4, 3, 2], 3)]
+#[case("query=date:%3E2025-01-04", &[5], 1)]
+#[case("query=date:%3C2025-01-02", &[1], 1)]
+#[case("query=system:system-1", &[2], 1)]
+#[case("query=system:system-*", &[3, 2], 2)]
+#[case("query=system:system", &[], 0)]
+#[case("query=-system:system-1", &[5, 4, 3, 1], 4)]
+#[case("query=type:type-?", &[3, 2], 2)]
+#[case("query=type:event_payload", &[5, 4], 2)]
+#[case("query=entity-event-id:2", &[5], 1)]
+#[case("query=user-agent:agent/*", &[2], 1)]
+#[case("query=header:header-2", &[1], 1)]
+#[case("query=header:HEADER-1=value-1", &[2, 1], 2)]
+#[case("query=header:header-1=value", &[], 0)]
+#[case("query=header:header-1=*-2", &[], 0)]
+#[case("query=header:header-2=*-2", &[1], 1)]
+#[case("query=header:header-1~^value-%5Cd$", &[2, 1], 2)]
+#[case("query=header:header-1~^VALUE", &[], 0)]
+#[case("query=header:header-1~^VALUE&ignoreCase=true", &[2, 1], 2)]
+#[case("query=header:header-1=VALUE-1", &[], 0)]
+#[case("query=header:header-1=VALUE-1&ignoreCase=true", &[2, 1], 2)]
+#[case("query=header:header-2=*-2&ignoreCase=true", &[1], 1)]
+#[case("query=header-2:LUE-2", &[], 0)]
+#[case("query=header-2:LUE-2&ignoreCase=true", &[1], 1)]
+#[case("query=system:SYSTEM-1", &[], 0)]
+#[case("query=system:SYSTEM-1&ignoreCase=true", &[2], 1)]
+#[case("query=type:TYPE-?&ignoreCase=true", &[3, 2], 2)]
+#[case("query=body-1", &[1], 1)]
+#[case("query=BODY-1", &[], 0)]
+#[case("query=BODY-1&ignoreCase=true", &[1], 1)]
+#[case("query=body_1", &[], 0)]
+#[case("query=system:system-[1]*", &[], 0)]
+#[case("query=system:SYSTEM-[1]*&ignoreCase=true", &[], 0)]
+#[case("query=regex:BODY-[12]&ignoreCase=true", &[], 0)]
 #[case("system=system-1", &[2], 1)]
 #[case("system=system-1,system-2", &[3, 2], 2)]
 #[case("system=system-xxx", &[], 0)]
@@ -47,6 +99,24 @@ async fn test_get_item_nonexistent(repository: SqlitePool) -> Result<()> {
 #[case("batchSize=1", &[5], 5)]
 #[case("batchSize=2", &[5, 4], 5)]
 #[case("batchSize=3", &[5, 4, 3], 5)]
+#[case("batchSize=4294967295", &[5, 4, 3, 2, 1], 5)]
+#[case("sort=id", &[1, 2, 3, 4, 5], 5)]
+#[case("sort=-id", &[5, 4, 3, 2, 1], 5)]
+#[case("sort=system", &[5, 4, 1, 2, 3], 5)]
+#[case("sort=system&asc=true", &[1, 4, 5, 2, 3], 5)]
+#[case("sort=-system", &[3, 2, 5, 4, 1], 5)]
+#[case("sort=type,-system", &[1, 5, 4, 3, 2], 5)]
+#[case("sort=-size", &[4, 2, 1, 3, 5], 5)]
+#[case("sort=size", &[5, 3, 2, 1, 4], 5)]
+#[case("sort=-submitDate", &[5, 4, 3, 2, 1], 5)]
+#[case("sort=entity_event_id,id", &[1, 2, 3, 4, 5], 5)]
+#[case("sort=-size&batchSize=2", &[4, 2], 5)]
+#[case("sort=-size&afterItemId=1", &[3, 5], 5)]
+#[case("sort=-size&afterItemId=1&batchSize=1", &[3], 5)]
+#[case("sort=system&afterItemId=4", &[1, 2, 3], 5)]
+#[case("sort=-system&afterItemId=2", &[5, 4, 1], 5)]
+#[case("afterItemId=3", &[2, 1], 5)]
+#[case("asc=true&afterItemId=3", &[4, 5], 5)]
 #[sqlx::test(fixtures("items"))]
 async fn test_get_items(
     #[case] filter: &str,
@@ -55,26 +125,796 @@ async fn test_get_items(
     #[ignore] pool_options: SqlitePoolOptions,
     #[ignore] connect_options: SqliteConnectOptions,
 ) -> Result<()> {
-    let repository = pool_options
-        .after_connect(|connection, _| {
-            Box::pin(async move {
-                unsafe {
-                    register_match_function(connection).await.unwrap();
-                }
+    let repository = connect(pool_options, connect_options).await?;
+    let uri: Uri = format!("http://localhost?{filter}").parse()?;
+    let filter: Query<ItemFilter> = Query::try_from_uri(&uri)?;
+    let page = repository.get_items(&filter).await?;
+    let item_ids: Vec<i64> = page.items.iter().map(|item| item.id).collect();
 
-                Ok(())
-            })
-        })
-        .connect_with(connect_options)
+    assert_eq!(item_ids, expected_item_ids);
+    assert_eq!(page.total_items, Some(expected_total_items));
+
+    Ok(())
+}
+
+#[rstest]
+#[case("", Some(5))]
+#[case("count=exact", Some(5))]
+#[case("count=estimated", Some(5))]
+#[case("count=none", None)]
+#[case("system=system-1", Some(1))]
+#[case("system=system-1,system-2&type=type-1", Some(1))]
+#[case("type=event_payload&count=estimated", Some(2))]
+#[case("system=&count=estimated", Some(0))]
+#[case("query=id:1..3", Some(3))]
+#[case("query=id:1..3&count=none", None)]
+#[case("batchSize=1&firstItemId=6", Some(5))]
+#[sqlx::test(fixtures("items"))]
+async fn test_get_items_count(
+    #[case] filter: &str,
+    #[case] expected_total_items: Option<i32>,
+    #[ignore] pool_options: SqlitePoolOptions,
+    #[ignore] connect_options: SqliteConnectOptions,
+) -> Result<()> {
+    let repository = connect(pool_options, connect_options).await?;
+    let page = get_page(&repository, filter, None).await?;
+
+    assert_eq!(page.total_items, expected_total_items);
+    assert!(!page.total_items_capped);
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("items"))]
+async fn test_get_items_count_counters(
+    pool_options: SqlitePoolOptions,
+    connect_options: SqliteConnectOptions,
+) -> Result<()> {
+    let repository = connect(pool_options, connect_options).await?;
+
+    sqlx::query("DELETE FROM item WHERE id = 4")
+        .execute(&repository)
+        .await?;
+
+    sqlx::query("UPDATE item SET system = 'system-1' WHERE id = 3")
+        .execute(&repository)
         .await?;
 
+    for (filter, expected_total_items) in [
+        ("", 4),
+        ("system=system-1", 2),
+        ("system=system-2", 0),
+        ("type=event_payload", 1),
+    ] {
+        let page = get_page(&repository, filter, None).await?;
+        assert_eq!(page.total_items, Some(expected_total_items));
+    }
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_get_items_count_estimated(
+    pool_options: SqlitePoolOptions,
+    connect_options: SqliteConnectOptions,
+) -> Result<()> {
+    let repository = connect(pool_options, connect_options).await?;
+
+    sqlx::query("WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 10001) INSERT INTO item (system) SELECT 'system' FROM n")
+        .execute(&repository)
+        .await?;
+
+    let page = get_page(
+        &repository,
+        "query=system:system&count=estimated&batchSize=1",
+        None,
+    )
+    .await?;
+    assert_eq!(page.total_items, Some(10_000));
+    assert!(page.total_items_capped);
+
+    let page = get_page(&repository, "query=system:system&batchSize=1", None).await?;
+    assert_eq!(page.total_items, Some(10_001));
+    assert!(!page.total_items_capped);
+
+    let page = get_page(&repository, "count=estimated&batchSize=1", None).await?;
+    assert_eq!(page.total_items, Some(10_001));
+    assert!(!page.total_items_capped);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("query=json:$.entity.vacancyId", &[2, 1])]
+#[case("query=json:$.entity.vacancyId=42", &[1])]
+#[case("query=json:$.entity.vacancyId=43", &[2])]
+#[case("query=json:$.eventDesc~Hired", &[1])]
+#[case("query=json:$.eventDesc~(?i)vacancy", &[2])]
+#[case("query=json:$.tags[*]=b", &[2])]
+#[case("query=json:$.missing", &[])]
+#[case("query=-json:$.entity.vacancyId=42", &[6, 5, 4, 3, 2])]
+#[case("query=xpath://vacancyId", &[3])]
+#[case("query=xpath://vacancyId=42", &[3])]
+#[case("query=xpath://vacancyId=43", &[])]
+#[case("query=xpath://Folder[@type='CL']/@id=123", &[6])]
+#[case("query=xpath://Folder[@type='FS']/@id=123", &[])]
+#[case("query=xpath://Folder/@nil=false", &[6])]
+#[case("query=xpath://Folder/@id~^4", &[6])]
+#[case("query=xpath:count(//Folder)=2", &[6])]
+#[sqlx::test(fixtures("documents"))]
+async fn test_get_items_documents(
+    #[case] filter: &str,
+    #[case] expected_item_ids: &[i64],
+    #[ignore] pool_options: SqlitePoolOptions,
+    #[ignore] connect_options: SqliteConnectOptions,
+) -> Result<()> {
+    let repository = connect(pool_options, connect_options).await?;
     let uri: Uri = format!("http://localhost?{filter}").parse()?;
     let filter: Query<ItemFilter> = Query::try_from_uri(&uri)?;
-    let (items, total_items) = repository.get_items(&filter).await?;
-    let item_ids: Vec<i64> = items.iter().map(|item| item.id).collect();
+    let page = repository.get_items(&filter).await?;
+    let item_ids: Vec<i64> = page.items.iter().map(|item| item.id).collect();
+
+    assert_eq!(item_ids, expected_item_ids);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("batchSize=2", vec![vec![5, 4], vec![3, 2], vec![1]])]
+#[case("asc=true&batchSize=2", vec![vec![1, 2], vec![3, 4], vec![5]])]
+#[case("sort=-size&batchSize=2", vec![vec![4, 2], vec![1, 3], vec![5]])]
+#[case("sort=type,-system&batchSize=3", vec![vec![1, 5, 4], vec![3, 2]])]
+#[case("query=-id:3&sort=-submitDate&batchSize=2", vec![vec![5, 4], vec![2, 1]])]
+#[case("batchSize=4294967295", vec![vec![5, 4, 3, 2, 1]])]
+#[case("batchSize=5", vec![vec![5, 4, 3, 2, 1]])]
+#[sqlx::test(fixtures("items"))]
+async fn test_get_items_cursor(
+    #[case] filter: &str,
+    #[case] expected_pages: Vec<Vec<i64>>,
+    #[ignore] pool_options: SqlitePoolOptions,
+    #[ignore] connect_options: SqliteConnectOptions,
+) -> Result<()> {
+    let repository = connect(pool_options, connect_options).await?;
+    let mut pages = Vec::new();
+    let mut page = get_page(&repository, filter, None).await?;
+
+    assert!(page.prev.is_none());
+
+    loop {
+        pages.push(page.items.iter().map(|item| item.id).collect::<Vec<_>>());
+
+        let Some(next) = page.next else {
+            break;
+        };
+
+        page = get_page(&repository, filter, Some(&next)).await?;
+    }
+
+    assert_eq!(pages, expected_pages);
+
+    pages.pop();
+
+    while let Some(prev) = page.prev {
+        page = get_page(&repository, filter, Some(&prev)).await?;
+
+        assert_eq!(
+            page.items.iter().map(|item| item.id).collect::<Vec<_>>(),
+            pages.pop().unwrap()
+        );
+    }
+
+    assert!(pages.is_empty());
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("items"))]
+async fn test_get_items_cursor_after_delete(
+    pool_options: SqlitePoolOptions,
+    connect_options: SqliteConnectOptions,
+) -> Result<()> {
+    let repository = connect(pool_options, connect_options).await?;
+    let page = get_page(&repository, "batchSize=2", None).await?;
+
+    sqlx::query("DELETE FROM item WHERE id IN (3, 4)")
+        .execute(&repository)
+        .await?;
+
+    let page = get_page(&repository, "batchSize=2", page.next.as_deref()).await?;
+
+    assert_eq!(
+        page.items.iter().map(|item| item.id).collect::<Vec<_>>(),
+        &[2, 1]
+    );
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("items"))]
+async fn test_get_items_cursor_invalid(
+    pool_options: SqlitePoolOptions,
+    connect_options: SqliteConnectOptions,
+) -> Result<()> {
+    let repository = connect(pool_options, connect_options).await?;
+    let page = get_page(&repository, "batchSize=2", None).await?;
+    let next = page.next.unwrap();
+
+    for (filter, cursor) in [("batchSize=2", "abc"), ("asc=true&batchSize=2", &next)] {
+        let error = get_page(&repository, filter, Some(cursor))
+            .await
+            .err()
+            .unwrap();
+
+        assert!(error.is::<CursorError>());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_get_items_invalid_sort() {
+    let uri: Uri = "http://localhost?sort=body".parse().unwrap();
+    let filter = Query::<ItemFilter>::try_from_uri(&uri);
+
+    assert!(filter.is_err());
+}
+
+#[sqlx::test(fixtures("items"))]
+async fn test_get_facets(
+    pool_options: SqlitePoolOptions,
+    connect_options: SqliteConnectOptions,
+) -> Result<()> {
+    let repository = connect(pool_options, connect_options).await?;
+    let uri: Uri = "http://localhost?query=-id:1".parse()?;
+    let filter: Query<ItemFilter> = Query::try_from_uri(&uri)?;
+    let facets = repository.get_facets(&filter).await?;
+
+    let facet = |facet: &str, value: Option<&str>, count| FacetValue {
+        facet: facet.into(),
+        value: value.map(Into::into),
+        count,
+    };
+
+    assert_eq!(
+        facets,
+        &[
+            facet("entityEventId", Some("1"), 1),
+            facet("entityEventId", Some("2"), 1),
+            facet("system", None, 2),
+            facet("system", Some("system-1"), 1),
+            facet("system", Some("system-2"), 1),
+            facet("type", Some("event_payload"), 2),
+            facet("type", Some("type-1"), 1),
+            facet("type", Some("type-2"), 1),
+            facet("userAgent", None, 3),
+            facet("userAgent", Some("agent/1.0"), 1),
+        ]
+    );
+
+    Ok(())
+}
+
+#[rstest]
+#[case("query=id:1..3", HistogramBucket::Day, None, &[("2025-01-01", None, 1), ("2025-01-02", None, 1), ("2025-01-03", None, 1)])]
+#[case("query=id:1", HistogramBucket::Hour, None, &[("2025-01-01 00:00:00", None, 1)])]
+#[case("query=id:2", HistogramBucket::Minute, None, &[("2025-01-02 00:00:00", None, 1)])]
+#[case("query=id:3..4", HistogramBucket::Day, Some(HistogramSplit::Type), &[("2025-01-03", Some("type-1"), 1), ("2025-01-04", Some("event_payload"), 1)])]
+#[case("system=system-1,system-2", HistogramBucket::Day, Some(HistogramSplit::System), &[("2025-01-02", Some("system-1"), 1), ("2025-01-03", Some("system-2"), 1)])]
+#[case("query=id:6", HistogramBucket::Day, None, &[])]
+#[sqlx::test(fixtures("items"))]
+async fn test_get_histogram(
+    #[case] filter: &str,
+    #[case] bucket: HistogramBucket,
+    #[case] split: Option<HistogramSplit>,
+    #[case] expected_counts: &[(&str, Option<&str>, i32)],
+    #[ignore] pool_options: SqlitePoolOptions,
+    #[ignore] connect_options: SqliteConnectOptions,
+) -> Result<()> {
+    let repository = connect(pool_options, connect_options).await?;
+    let uri: Uri = format!("http://localhost?{filter}").parse()?;
+    let filter: Query<ItemFilter> = Query::try_from_uri(&uri)?;
+    let counts = repository.get_histogram(&filter, bucket, split).await?;
+
+    let expected_counts: Vec<_> = expected_counts
+        .iter()
+        .map(|(start, group, count)| HistogramCount {
+            start: (*start).into(),
+            group: group.map(Into::into),
+            count: *count,
+        })
+        .collect();
+
+    assert_eq!(counts, expected_counts);
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("items"))]
+async fn test_item_tags_and_notes(
+    pool_options: SqlitePoolOptions,
+    connect_options: SqliteConnectOptions,
+) -> Result<()> {
+    let repository = connect(pool_options, connect_options).await?;
+
+    assert!(repository.add_item_tag(2, "ticket PROJ-123").await?);
+    assert!(repository.add_item_tag(2, "bad payload").await?);
+    assert!(repository.add_item_tag(2, "bad payload").await?);
+    assert!(repository.add_item_tag(3, "bad payload").await?);
+    assert!(!repository.add_item_tag(6, "bad payload").await?);
+
+    let note = NewItemNote {
+        author: Some("author".into()),
+        text: "text".into(),
+    };
+
+    let note = repository.add_item_note(2, &note).await?.unwrap();
+
+    assert_eq!(note.author.as_deref(), Some("author"));
+    assert_eq!(note.text, "text");
+
+    let missing_note = NewItemNote {
+        author: None,
+        text: "text".into(),
+    };
+
+    assert!(repository.add_item_note(6, &missing_note).await?.is_none());
+
+    let item = repository.get_item(2).await?.unwrap();
+
+    assert_eq!(*item.summary.tags, &["bad payload", "ticket PROJ-123"]);
+    assert_eq!(item.notes, &[note]);
+
+    let page = get_page(&repository, "query=tag:bad*", None).await?;
+    let tags: Vec<_> = page
+        .items
+        .iter()
+        .map(|item| (item.id, item.tags.join(",")))
+        .collect();
+
+    assert_eq!(
+        tags,
+        &[
+            (3, "bad payload".into()),
+            (2, "bad payload,ticket PROJ-123".into())
+        ]
+    );
+
+    assert!(repository.remove_item_tag(2, "bad payload").await?);
+    assert!(!repository.remove_item_tag(2, "bad payload").await?);
+    assert!(!repository.remove_item_note(3, item.notes[0].id).await?);
+    assert!(repository.remove_item_note(2, item.notes[0].id).await?);
+
+    let item = repository.get_item(2).await?.unwrap();
+
+    assert_eq!(*item.summary.tags, &["ticket PROJ-123"]);
+    assert!(item.notes.is_empty());
+
+    let page = get_page(&repository, "query=-tag:*", None).await?;
+    let item_ids: Vec<_> = page.items.iter().map(|item| item.id).collect();
+
+    assert_eq!(item_ids, &[5, 4, 1]);
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("items"))]
+async fn test_pinned_items(repository: SqlitePool) -> Result<()> {
+    assert!(repository.set_item_pinned(2, true).await?);
+    assert!(repository.set_item_pinned(4, true).await?);
+    assert!(!repository.set_item_pinned(6, true).await?);
+
+    assert!(repository.get_item(2).await?.unwrap().summary.pinned);
+    assert!(!repository.get_item(3).await?.unwrap().summary.pinned);
+
+    for (filter, expected_item_ids) in [
+        ("pinned=true", &[4, 2][..]),
+        ("pinned=false", &[5, 3, 1]),
+        ("system=system-1&pinned=true", &[2]),
+        ("system=system-1&pinned=false", &[]),
+        ("count=estimated&pinned=true", &[4, 2]),
+    ] {
+        let page = get_page(&repository, filter, None).await?;
+        let item_ids: Vec<_> = page.items.iter().map(|item| item.id).collect();
+
+        assert_eq!(item_ids, expected_item_ids);
+        assert_eq!(page.total_items, Some(expected_item_ids.len() as i32));
+        assert!(
+            page.items
+                .iter()
+                .all(|item| item.pinned == filter.ends_with("true"))
+        );
+    }
+
+    let result = sqlx::query("DELETE FROM item WHERE id IN (1, 2)")
+        .execute(&repository)
+        .await;
+
+    assert!(result.is_err());
+    assert!(repository.get_item(1).await?.is_some());
+
+    assert!(repository.set_item_pinned(2, false).await?);
+
+    sqlx::query("DELETE FROM item WHERE id = 2")
+        .execute(&repository)
+        .await?;
+
+    assert!(repository.get_item(2).await?.is_none());
+
+    Ok(())
+}
+
+#[rstest]
+#[case("query=field:vacancyId", &[4, 2])]
+#[case("query=field:vacancyid=42", &[2])]
+#[case("query=field:vacancyId=4*", &[4, 2])]
+#[case("query=field:vacancyId~^43$", &[4])]
+#[case("query=field:personId", &[])]
+#[case("query=-field:vacancyId", &[5, 3, 1])]
+#[sqlx::test(fixtures("items"))]
+async fn test_get_items_field(
+    #[case] filter: &str,
+    #[case] expected_item_ids: &[i64],
+    #[ignore] pool_options: SqlitePoolOptions,
+    #[ignore] connect_options: SqliteConnectOptions,
+) -> Result<()> {
+    let repository = connect(pool_options, connect_options).await?;
+
+    sqlx::query(
+        "INSERT INTO item_field (item_id, name, value) VALUES (2, 'vacancyId', '42'), (4, 'vacancyId', '43')",
+    )
+    .execute(&repository)
+    .await?;
+
+    let page = get_page(&repository, filter, None).await?;
+    let item_ids: Vec<_> = page.items.iter().map(|item| item.id).collect();
 
     assert_eq!(item_ids, expected_item_ids);
-    assert_eq!(total_items, expected_total_items);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("query=event:GROUP-A/TYPE-1", &[4])]
+#[case("query=event:GROUP-A", &[4])]
+#[case("query=event:TYPE-2", &[5])]
+#[case("query=event:*/*", &[5, 4])]
+#[case("query=event:GROUP-?", &[5, 4])]
+#[case("query=event:group-b&ignoreCase=true", &[5])]
+#[case("query=-event:GROUP-A", &[5, 3, 2, 1])]
+#[case("query=event:GROUP-C", &[])]
+#[case("eventType=GROUP-B", &[5, 3, 2])]
+#[case("eventType=TYPE-1,2", &[5, 4, 3, 2])]
+#[sqlx::test(fixtures("items"))]
+async fn test_get_items_event(
+    #[case] filter: &str,
+    #[case] expected_item_ids: &[i64],
+    #[ignore] pool_options: SqlitePoolOptions,
+    #[ignore] connect_options: SqliteConnectOptions,
+) -> Result<()> {
+    let repository = connect(pool_options, connect_options).await?;
+
+    repository
+        .set_event_types(&[
+            EventTypeGroup {
+                name: "GROUP-A".into(),
+                types: vec![EventType {
+                    name: "TYPE-1".into(),
+                    id: 1,
+                }],
+            },
+            EventTypeGroup {
+                name: "GROUP-B".into(),
+                types: vec![EventType {
+                    name: "TYPE-2".into(),
+                    id: 2,
+                }],
+            },
+        ])
+        .await?;
+
+    let page = get_page(&repository, filter, None).await?;
+    let item_ids: Vec<_> = page.items.iter().map(|item| item.id).collect();
+
+    assert_eq!(item_ids, expected_item_ids);
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("items"))]
+async fn test_get_items_valid(repository: SqlitePool) -> Result<()> {
+    sqlx::query("UPDATE item SET valid = id % 2 WHERE id IN (2, 3, 4)")
+        .execute(&repository)
+        .await?;
+
+    for (filter, expected_item_ids) in [
+        ("valid=false", &[4, 2][..]),
+        ("valid=true", &[3]),
+        ("", &[5, 4, 3, 2, 1]),
+    ] {
+        let page = get_page(&repository, filter, None).await?;
+        let item_ids: Vec<_> = page.items.iter().map(|item| item.id).collect();
+
+        assert_eq!(item_ids, expected_item_ids);
+        assert_eq!(page.total_items, Some(expected_item_ids.len() as i32));
+    }
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("items"))]
+async fn test_set_event_types(repository: SqlitePool) -> Result<()> {
+    let group = |name: &str, event_type: &str, id| EventTypeGroup {
+        name: name.into(),
+        types: vec![EventType {
+            name: event_type.into(),
+            id,
+        }],
+    };
+
+    repository
+        .set_event_types(&[group("GROUP-A", "TYPE-1", 1)])
+        .await?;
+
+    let item = repository.get_item(4).await?.unwrap();
+
+    assert_eq!(item.summary.event_group.as_deref(), Some("GROUP-A"));
+    assert_eq!(item.summary.event_type.as_deref(), Some("TYPE-1"));
+
+    repository
+        .set_event_types(&[group("GROUP-B", "TYPE-2", 2)])
+        .await?;
+
+    let page = get_page(&repository, "", None).await?;
+    let event_types: Vec<_> = page
+        .items
+        .iter()
+        .map(|item| {
+            (
+                item.id,
+                item.event_group.as_deref(),
+                item.event_type.as_deref(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        event_types,
+        &[
+            (5, Some("GROUP-B"), Some("TYPE-2")),
+            (4, None, None),
+            (3, None, None),
+            (2, None, None),
+            (1, None, None),
+        ]
+    );
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("items"))]
+async fn test_set_item_classification(repository: SqlitePool) -> Result<()> {
+    sqlx::query("INSERT INTO item_field (item_id, name, value) VALUES (2, 'vacancyId', '42')")
+        .execute(&repository)
+        .await?;
+
+    let classification = ItemClassification {
+        system: Some("system-3".into()),
+        r#type: Some("type-3".into()),
+        entity_event_id: Some(9),
+        fields: vec![ItemField {
+            name: "personId".into(),
+            value: "7".into(),
+        }],
+        valid: Some(true),
+        validation_errors: Vec::new(),
+    };
+
+    assert!(
+        repository
+            .set_item_classification(2, &classification)
+            .await?
+    );
+    assert!(
+        !repository
+            .set_item_classification(99, &classification)
+            .await?
+    );
+
+    let item = repository.get_item(2).await?.unwrap();
+
+    assert_eq!(item.summary.system.as_deref(), Some("system-3"));
+    assert_eq!(item.summary.r#type.as_deref(), Some("type-3"));
+    assert_eq!(item.summary.entity_event_id, Some(9));
+    assert_eq!(item.fields, classification.fields);
+    assert_eq!(item.summary.valid, Some(true));
+    assert!(repository.get_systems().await?.contains(&"system-3".into()));
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("items"))]
+async fn test_get_related_items(repository: SqlitePool) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO item (id, type, event_id, entity_event_id, submit_date) VALUES
+            (10, 'event_notification', 7, 8, '2025-02-02'),
+            (11, 'event_payload', NULL, 8, '2025-02-01'),
+            (12, 'event_notification', 7, 8, '2025-02-03'),
+            (13, 'event_notification', 9, 10, '2025-02-04')",
+    )
+    .execute(&repository)
+    .await?;
+
+    for (id, expected_item_ids) in [
+        (10, &[10, 12][..]),
+        (11, &[11]),
+        (12, &[10, 12]),
+        (13, &[13]),
+        (1, &[1]),
+        (99, &[]),
+    ] {
+        let item_ids: Vec<_> = repository
+            .get_related_items(id)
+            .await?
+            .iter()
+            .map(|item| item.id)
+            .collect();
+
+        assert_eq!(item_ids, expected_item_ids);
+    }
+
+    sqlx::query(
+        "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 150) \
+        INSERT INTO item (type, event_id) SELECT 'event_notification', 20 FROM n",
+    )
+    .execute(&repository)
+    .await?;
+
+    let items = repository.get_related_items(14).await?;
+
+    assert_eq!(items.len(), 100);
+    assert_eq!(repository.get_timeline(20).await?.len(), 100);
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("items"))]
+async fn test_get_timeline(repository: SqlitePool) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO item (id, type, system, event_id, entity_event_id, submit_date) VALUES
+            (10, 'event_notification', NULL, 7, 44, '2025-02-01 10:00:00'),
+            (11, 'event_payload', NULL, 7, 44, '2025-02-01 10:00:05'),
+            (12, 'status_updated', 'system-1', 7, NULL, '2025-02-01 10:01:05'),
+            (13, 'event_notification', NULL, 8, 44, '2025-02-01 10:02:00')",
+    )
+    .execute(&repository)
+    .await?;
+
+    let steps: Vec<_> = repository
+        .get_timeline(7)
+        .await?
+        .iter()
+        .map(|step| (step.summary.id, step.gap_seconds))
+        .collect();
+
+    assert_eq!(steps, &[(10, None), (11, Some(5)), (12, Some(60))]);
+    assert!(repository.get_timeline(99).await?.is_empty());
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_saved_searches(repository: SqlitePool) -> Result<()> {
+    let search = NewSavedSearch {
+        owner: Some("owner".into()),
+        description: None,
+        filter: json!({ "query": "id:1" }),
+    };
+
+    repository.save_saved_search("search-2", &search).await?;
+    repository.save_saved_search("search-1", &search).await?;
+
+    let search = NewSavedSearch {
+        owner: None,
+        description: Some("description".into()),
+        filter: json!({ "system": "system-1" }),
+    };
+
+    repository.save_saved_search("search-1", &search).await?;
+
+    let saved_search = repository.get_saved_search("search-1").await?.unwrap();
+
+    assert_eq!(saved_search.name, "search-1");
+    assert_eq!(saved_search.owner, None);
+    assert_eq!(saved_search.description.as_deref(), Some("description"));
+    assert_eq!(saved_search.filter, json!({ "system": "system-1" }));
+
+    let names: Vec<_> = repository
+        .get_saved_searches()
+        .await?
+        .into_iter()
+        .map(|search| search.name)
+        .collect();
+
+    assert_eq!(names, &["search-1", "search-2"]);
+
+    assert!(repository.delete_saved_search("search-1").await?);
+    assert!(!repository.delete_saved_search("search-1").await?);
+    assert!(repository.get_saved_search("search-1").await?.is_none());
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("items"))]
+async fn test_get_stats(repository: SqlitePool) -> Result<()> {
+    repository
+        .insert_item(&NewItem {
+            system: Some("system-1"),
+            r#type: None,
+            event_id: None,
+            entity_event_id: None,
+            user_agent: Some("agent/1.0"),
+            path: None,
+            headers: &[],
+            fields: &[],
+            valid: None,
+            validation_errors: &[],
+            body: b"body",
+        })
+        .await?;
+
+    let stats = repository.get_stats().await?;
+
+    assert_eq!(stats.total_items, 6);
+    assert_eq!(stats.total_bytes, 58);
+    assert_eq!(stats.items_last_hour, 1);
+
+    let systems: Vec<_> = stats
+        .systems
+        .iter()
+        .map(|system| {
+            (
+                system.system.as_deref(),
+                system.count,
+                system.average_size,
+                system.first_seen.as_str(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        systems,
+        &[
+            (None, 3, 38.0 / 3.0, "2025-01-01"),
+            (Some("system-1"), 2, 8.0, "2025-01-02"),
+            (Some("system-2"), 1, 4.0, "2025-01-03"),
+        ]
+    );
+
+    assert_eq!(stats.systems[2].last_seen, "2025-01-03");
+
+    let types: Vec<_> = stats
+        .types
+        .iter()
+        .map(|r#type| (r#type.r#type.as_deref(), r#type.count, r#type.average_size))
+        .collect();
+
+    assert_eq!(
+        types,
+        &[
+            (None, 2, 8.0),
+            (Some("event_payload"), 2, 13.0),
+            (Some("type-1"), 1, 4.0),
+            (Some("type-2"), 1, 12.0),
+        ]
+    );
+
+    assert_eq!(
+        stats.user_agents,
+        &[UserAgentStats {
+            user_agent: "agent/1.0".into(),
+            count: 2,
+        }]
+    );
 
     Ok(())
 }
@@ -93,6 +933,7 @@ async fn test_insert_and_get_item(repository: SqlitePool) -> Result<()> {
     const EVENT_ID: Option<i64> = Some(123);
     const ENTITY_EVENT_ID: Option<i64> = Some(456);
     const USER_AGENT: Option<&str> = Some("user-agent");
+    const PATH: Option<&str> = Some("/sink/path");
     const HEADER_1_NAME: &str = "header-1";
     const HEADER_1_VALUE: &[u8] = b"value-1";
     const HEADER_2_NAME: &str = "header-2";
@@ -105,6 +946,7 @@ async fn test_insert_and_get_item(repository: SqlitePool) -> Result<()> {
         event_id: EVENT_ID,
         entity_event_id: ENTITY_EVENT_ID,
         user_agent: USER_AGENT,
+        path: PATH,
         headers: &[
             NewItemHeader {
                 name: HEADER_1_NAME,
@@ -115,6 +957,21 @@ async fn test_insert_and_get_item(repository: SqlitePool) -> Result<()> {
                 value: HEADER_2_VALUE,
             },
         ],
+        fields: &[
+            ItemField {
+                name: "personId".into(),
+                value: "7".into(),
+            },
+            ItemField {
+                name: "vacancyId".into(),
+                value: "42".into(),
+            },
+        ],
+        valid: Some(false),
+        validation_errors: &[ItemValidationError {
+            path: "/entityEventId".into(),
+            message: "\"1\" is not of type \"integer\"".into(),
+        }],
         body: BODY,
     };
 
@@ -130,13 +987,44 @@ async fn test_insert_and_get_item(repository: SqlitePool) -> Result<()> {
     assert_eq!(summary.event_id, EVENT_ID);
     assert_eq!(summary.entity_event_id, ENTITY_EVENT_ID);
     assert_eq!(summary.user_agent.as_deref(), USER_AGENT);
+    assert_eq!(summary.path.as_deref(), PATH);
     assert!(!summary.submit_date.is_empty());
     assert_eq!(headers.len(), 2);
     assert_eq!(headers[0].name, HEADER_1_NAME);
     assert_eq!(*headers[0].value, *HEADER_1_VALUE);
     assert_eq!(headers[1].name, HEADER_2_NAME);
     assert_eq!(*headers[1].value, *HEADER_2_VALUE);
+    assert_eq!(item.fields, new_item.fields);
+    assert_eq!(summary.valid, Some(false));
+    assert_eq!(item.validation_errors, new_item.validation_errors);
     assert_eq!(*item.body, *BODY);
 
     Ok(())
 }
+
+async fn get_page(repository: &SqlitePool, filter: &str, cursor: Option<&str>) -> Result<ItemPage> {
+    let cursor = cursor.map(|cursor| format!("&cursor={cursor}"));
+    let uri: Uri = format!("http://localhost?{filter}{}", cursor.unwrap_or_default()).parse()?;
+    let filter: Query<ItemFilter> = Query::try_from_uri(&uri)?;
+
+    repository.get_items(&filter).await
+}
+
+async fn connect(
+    pool_options: SqlitePoolOptions,
+    connect_options: SqliteConnectOptions,
+) -> Result<SqlitePool> {
+    pool_options
+        .after_connect(|connection, _| {
+            Box::pin(async move {
+                unsafe {
+                    register_match_function(connection).await.unwrap();
+                }
+
+                Ok(())
+            })
+        })
+        .connect_with(connect_options)
+        .await
+        .map_err(Into::into)
+}
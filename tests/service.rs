@@ -1,10 +1,18 @@
+use std::time::Duration;
+
 use anyhow::Result;
 use axum::{extract::Query, http::Uri};
 use rstest::rstest;
+use serde_json::{Value, json};
 
 use sink::{
-    model::{ItemFilter, NewItemHeader},
-    service::{Service, new_service},
+    model::{
+        DiffChange, DiffKind, DiffOp, DiffParams, FacetCount, ItemField, ItemFilter,
+        ItemRelationship, ItemType, ItemValidationError, NewItemHeader, NewItemNote,
+        NewSavedSearch, NotFoundError, ReclassifyParams, ValidationError,
+    },
+    query::QueryError,
+    service::{Service, ServiceOptions, new_service, new_service_with_options},
 };
 
 use sqlx::SqlitePool;
@@ -18,7 +26,7 @@ async fn test_get_item(
     #[case] expected_item_id: Option<i64>,
     #[ignore] repository: SqlitePool,
 ) -> Result<()> {
-    let service = new_service(repository)?;
+    let service = new_service(repository).await?;
     let item = service.get_item(id).await?;
 
     assert_eq!(item.map(|item| item.summary.id), expected_item_id);
@@ -37,14 +45,14 @@ async fn test_get_items(
     #[case] expected_first_item_id: Option<i64>,
     #[ignore] repository: SqlitePool,
 ) -> Result<()> {
-    let service = new_service(repository)?;
+    let service = new_service(repository).await?;
     let uri: Uri = format!("http://localhost?{filter}").parse()?;
     let filter: Query<ItemFilter> = Query::try_from_uri(&uri)?;
     let result = service.get_items(&filter).await?;
     let item_ids: Vec<i64> = result.items.iter().map(|item| item.id).collect();
 
     assert_eq!(item_ids, expected_item_ids);
-    assert_eq!(result.total_items, expected_total_items);
+    assert_eq!(result.total_items, Some(expected_total_items));
     assert_eq!(result.systems, &["system-1", "system-2"]);
 
     assert_eq!(
@@ -55,17 +63,622 @@ async fn test_get_items(
     Ok(())
 }
 
+#[sqlx::test(fixtures("items"))]
+async fn test_get_items_facets(repository: SqlitePool) -> Result<()> {
+    let service = new_service(repository).await?;
+    let uri: Uri = "http://localhost?facets=true&type=event_payload,type-1".parse()?;
+    let filter: Query<ItemFilter> = Query::try_from_uri(&uri)?;
+    let facets = service.get_items(&filter).await?.facets.unwrap();
+
+    let count = |value: Option<&str>, count| FacetCount {
+        value: value.map(Into::into),
+        count,
+    };
+
+    assert_eq!(facets.system, &[count(None, 2), count(Some("system-2"), 1)]);
+
+    assert_eq!(
+        facets.r#type,
+        &[count(Some("event_payload"), 2), count(Some("type-1"), 1)]
+    );
+
+    assert_eq!(facets.event_group, &[count(Some("ONBOARDING_PROCESS"), 2)]);
+
+    assert_eq!(facets.user_agent, &[count(None, 3)]);
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("items"))]
+async fn test_get_items_without_facets(repository: SqlitePool) -> Result<()> {
+    let service = new_service(repository).await?;
+    let uri: Uri = "http://localhost".parse()?;
+    let filter: Query<ItemFilter> = Query::try_from_uri(&uri)?;
+
+    assert!(service.get_items(&filter).await?.facets.is_none());
+
+    Ok(())
+}
+
+#[rstest]
+#[case("saved=search", &[1, 2, 3])]
+#[case("saved=search&asc=false", &[3, 2, 1])]
+#[case("saved=search&batchSize=1", &[1])]
+#[sqlx::test(fixtures("items"))]
+async fn test_get_items_saved(
+    #[case] filter: &str,
+    #[case] expected_item_ids: &[i64],
+    #[ignore] repository: SqlitePool,
+) -> Result<()> {
+    let service = new_service(repository).await?;
+
+    service
+        .save_saved_search(
+            "search",
+            &NewSavedSearch {
+                owner: None,
+                description: None,
+                filter: json!({ "query": "id:1..3", "asc": true }),
+            },
+        )
+        .await?;
+
+    let uri: Uri = format!("http://localhost?{filter}").parse()?;
+    let filter: Query<ItemFilter> = Query::try_from_uri(&uri)?;
+    let result = service.get_items(&filter).await?;
+    let item_ids: Vec<i64> = result.items.iter().map(|item| item.id).collect();
+
+    assert_eq!(item_ids, expected_item_ids);
+    assert_eq!(result.total_items, Some(3));
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("items"))]
+async fn test_get_items_saved_missing(repository: SqlitePool) -> Result<()> {
+    let service = new_service(repository).await?;
+    let uri: Uri = "http://localhost?saved=missing".parse()?;
+    let filter: Query<ItemFilter> = Query::try_from_uri(&uri)?;
+    let error = service.get_items(&filter).await.err().unwrap();
+
+    assert!(error.is::<NotFoundError>());
+
+    Ok(())
+}
+
+#[rstest]
+#[case(" ", json!({}))]
+#[case("search", json!("id:1"))]
+#[case("search", json!({ "asc": "yes" }))]
+#[case("search", json!({ "saved": "other" }))]
+#[case("search", json!({ "query": "(id:1" }))]
+#[sqlx::test]
+async fn test_save_saved_search_invalid(
+    #[case] name: &str,
+    #[case] filter: Value,
+    #[ignore] repository: SqlitePool,
+) -> Result<()> {
+    let service = new_service(repository).await?;
+
+    let result = service
+        .save_saved_search(
+            name,
+            &NewSavedSearch {
+                owner: None,
+                description: None,
+                filter,
+            },
+        )
+        .await;
+
+    let error = result.err().unwrap();
+
+    assert!(error.is::<ValidationError>() || error.is::<QueryError>());
+    assert!(service.get_saved_searches().await?.is_empty());
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("items"))]
+async fn test_item_tags_and_notes_invalid(repository: SqlitePool) -> Result<()> {
+    let service = new_service(repository).await?;
+
+    let error = service.add_item_tag(1, " ").await.err().unwrap();
+    assert!(error.is::<ValidationError>());
+
+    let note = NewItemNote {
+        author: None,
+        text: "\n".into(),
+    };
+
+    let error = service.add_item_note(1, &note).await.err().unwrap();
+    assert!(error.is::<ValidationError>());
+
+    assert!(service.add_item_tag(1, " tag ").await?);
+    assert_eq!(*service.get_item(1).await?.unwrap().summary.tags, &["tag"]);
+    assert!(service.remove_item_tag(1, "tag").await?);
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("items"))]
+async fn test_diff_items(repository: SqlitePool) -> Result<()> {
+    let service = new_service(repository).await?;
+
+    let diff = service
+        .diff_items(&DiffParams {
+            left: 1,
+            right: 2,
+            ignore: None,
+        })
+        .await?;
+
+    assert_eq!(diff.kind, DiffKind::Text);
+
+    assert_eq!(
+        diff.body,
+        &[
+            DiffChange {
+                path: "1".into(),
+                op: DiffOp::Removed,
+                left: Some(json!("xxxbody-1xxx")),
+                right: None,
+            },
+            DiffChange {
+                path: "1".into(),
+                op: DiffOp::Added,
+                left: None,
+                right: Some(json!("xxxbody-2xxx")),
+            },
+        ]
+    );
+
+    assert_eq!(
+        diff.headers,
+        &[DiffChange {
+            path: "header-2".into(),
+            op: DiffOp::Removed,
+            left: Some(json!("value-2")),
+            right: None,
+        }]
+    );
+
+    let diff = service
+        .diff_items(&DiffParams {
+            left: 1,
+            right: 2,
+            ignore: Some("header-*".into()),
+        })
+        .await?;
+
+    assert!(diff.headers.is_empty());
+
+    let error = service
+        .diff_items(&DiffParams {
+            left: 1,
+            right: 9,
+            ignore: None,
+        })
+        .await
+        .unwrap_err();
+
+    assert!(error.is::<NotFoundError>());
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("items"))]
+async fn test_get_event_types(repository: SqlitePool) -> Result<()> {
+    let service = new_service(repository.clone()).await?;
+    let event_types = service.get_event_types().await?;
+
+    assert!(
+        event_types
+            .iter()
+            .any(|group| group.name == "ONBOARDING_PROCESS")
+    );
+
+    let uri: Uri = "http://localhost?query=event:ONBOARDING_PROCESS/*".parse()?;
+    let filter: Query<ItemFilter> = Query::try_from_uri(&uri)?;
+    let result = service.get_items(&filter).await?;
+    let item_ids: Vec<i64> = result.items.iter().map(|item| item.id).collect();
+
+    assert_eq!(item_ids, &[5, 4]);
+    assert_eq!(result.items[1].event_type.as_deref(), Some("OB_STARTED"));
+
+    let path = std::env::temp_dir().join(format!("sink-event-types-{}", std::process::id()));
+    std::fs::write(
+        &path,
+        r#"[{"name": "CUSTOM", "types": [{"name": "CUSTOM_EVENT", "id": 2}]}]"#,
+    )?;
+
+    let service = new_service_with_options(
+        repository,
+        ServiceOptions {
+            event_types: Some(path.clone()),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let uri: Uri = "http://localhost?query=event:CUSTOM".parse()?;
+    let filter: Query<ItemFilter> = Query::try_from_uri(&uri)?;
+    let result = service.get_items(&filter).await?;
+
+    assert_eq!(service.get_event_types().await?.len(), 1);
+    assert_eq!(result.items.len(), 1);
+    assert_eq!(result.items[0].id, 5);
+    assert_eq!(result.items[0].event_group.as_deref(), Some("CUSTOM"));
+
+    std::fs::write(
+        &path,
+        r#"[{"name": "RELOADED", "types": [{"name": "RELOADED_EVENT", "id": 2}]}]"#,
+    )?;
+
+    let uri: Uri = "http://localhost?query=event:RELOADED".parse()?;
+    let filter: Query<ItemFilter> = Query::try_from_uri(&uri)?;
+    let mut result = service.get_items(&filter).await?;
+
+    for _ in 0..50 {
+        if !result.items.is_empty() {
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        result = service.get_items(&filter).await?;
+    }
+
+    std::fs::remove_file(&path)?;
+
+    assert_eq!(result.items.len(), 1);
+    assert_eq!(
+        result.items[0].event_type.as_deref(),
+        Some("RELOADED_EVENT")
+    );
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_get_item_types(repository: SqlitePool) -> Result<()> {
+    let service = new_service(repository.clone()).await?;
+    let item_types = service.get_item_types().await?;
+
+    assert!(
+        item_types
+            .iter()
+            .any(|item_type| item_type.key == "event_payload" && item_type.name == "Event Payload")
+    );
+
+    let path = std::env::temp_dir().join(format!("sink-item-types-{}", std::process::id()));
+    std::fs::write(
+        &path,
+        r#"[{"key": "custom", "color": "red", "matches": ["a"]}]"#,
+    )?;
+
+    let service = new_service_with_options(
+        repository,
+        ServiceOptions {
+            item_types: Some(path.clone()),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    std::fs::remove_file(&path)?;
+
+    assert_eq!(
+        service.get_item_types().await?,
+        &[ItemType {
+            key: "custom".into(),
+            name: "custom".into(),
+            color: Some("red".into()),
+        }]
+    );
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("items"))]
+async fn test_get_timeline(repository: SqlitePool) -> Result<()> {
+    sqlx::query("UPDATE item SET event_id = 7 WHERE id = 4")
+        .execute(&repository)
+        .await?;
+
+    let service = new_service(repository).await?;
+    let timeline = service.get_timeline(7).await?.unwrap();
+
+    assert_eq!(timeline.entity_event_id, Some(1));
+    assert_eq!(timeline.event_group.as_deref(), Some("ONBOARDING_PROCESS"));
+    assert_eq!(timeline.event_type.as_deref(), Some("OB_STARTED"));
+    assert_eq!(timeline.steps.len(), 1);
+    assert_eq!(
+        timeline.steps[0].summary.event_group.as_deref(),
+        Some("ONBOARDING_PROCESS")
+    );
+    assert_eq!(timeline.steps[0].relationship, ItemRelationship::Payload);
+
+    assert_eq!(
+        timeline.missing,
+        &[
+            ItemRelationship::Notification,
+            ItemRelationship::Acknowledgement
+        ]
+    );
+
+    assert!(service.get_timeline(99).await?.is_none());
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_save_item_validation(repository: SqlitePool) -> Result<()> {
+    let path = std::env::temp_dir().join(format!("sink-item-schemas-{}", std::process::id()));
+    std::fs::write(
+        &path,
+        r#"{"event_notification": {"type": "object", "required": ["eventId"]}}"#,
+    )?;
+
+    let service = new_service_with_options(
+        repository,
+        ServiceOptions {
+            item_schemas: Some(path.clone()),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    std::fs::remove_file(&path)?;
+
+    let invalid_id = service
+        .save_item(None, &[], br#"{"entityEventId": 1}"#)
+        .await?;
+
+    let valid_id = service
+        .save_item(None, &[], br#"{"entityEventId": 1, "eventId": 2}"#)
+        .await?;
+
+    let unchecked_id = service.save_item(None, &[], b"<a/>").await?;
+
+    let item = service.get_item(invalid_id).await?.unwrap();
+
+    assert_eq!(item.summary.valid, Some(false));
+    assert_eq!(
+        item.validation_errors,
+        &[ItemValidationError {
+            path: String::new(),
+            message: r#""eventId" is a required property"#.into(),
+        }]
+    );
+
+    let item = service.get_item(valid_id).await?.unwrap();
+
+    assert_eq!(item.summary.valid, Some(true));
+    assert!(item.validation_errors.is_empty());
+
+    let item = service.get_item(unchecked_id).await?.unwrap();
+
+    assert_eq!(item.summary.valid, None);
+
+    let uri: Uri = "http://localhost?valid=false".parse()?;
+    let filter: Query<ItemFilter> = Query::try_from_uri(&uri)?;
+    let result = service.get_items(&filter).await?;
+    let item_ids: Vec<i64> = result.items.iter().map(|item| item.id).collect();
+
+    assert_eq!(item_ids, &[invalid_id]);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_save_item_fields(repository: SqlitePool) -> Result<()> {
+    let service = new_service(repository).await?;
+
+    let id = service
+        .save_item(
+            None,
+            &[],
+            br#"<tns:updatedVacancy xmlns:tns="urn:vacancy"><tns:vacancyId>42</tns:vacancyId></tns:updatedVacancy>"#,
+        )
+        .await?;
+
+    let item = service.get_item(id).await?.unwrap();
+
+    assert_eq!(
+        item.fields,
+        &[ItemField {
+            name: "vacancyId".into(),
+            value: "42".into(),
+        }]
+    );
+
+    let uri: Uri = "http://localhost?query=field:vacancyId=42".parse()?;
+    let filter: Query<ItemFilter> = Query::try_from_uri(&uri)?;
+    let result = service.get_items(&filter).await?;
+
+    assert_eq!(result.items.len(), 1);
+    assert_eq!(result.items[0].id, id);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_reclassify_items(repository: SqlitePool) -> Result<()> {
+    let service = new_service(repository.clone()).await?;
+
+    let mut ids = Vec::new();
+
+    for body in [
+        &br#"{"entityEventId": 567, "vacancyId": 42}"#[..],
+        b"<mgsSystem>system</mgsSystem>",
+        b"",
+    ] {
+        ids.push(service.save_item(None, &[], body).await?);
+    }
+
+    sqlx::query("UPDATE item SET system = 'stale', type = NULL, entity_event_id = 1")
+        .execute(&repository)
+        .await?;
+
+    sqlx::query("DELETE FROM item_field")
+        .execute(&repository)
+        .await?;
+
+    let uri: Uri = "http://localhost?batchSize=1".parse()?;
+    let filter: Query<ItemFilter> = Query::try_from_uri(&uri)?;
+
+    let result = service
+        .reclassify_items(
+            &filter,
+            &ReclassifyParams {
+                dry_run: Some(true),
+            },
+        )
+        .await?;
+
+    assert!(result.dry_run);
+    assert_eq!(result.processed, 3);
+    assert_eq!(result.changed, 3);
+    assert_eq!(result.items[0].id, ids[0]);
+    assert_eq!(
+        result.items[0].changes,
+        &[
+            DiffChange {
+                path: "system".into(),
+                op: DiffOp::Removed,
+                left: Some(json!("stale")),
+                right: None,
+            },
+            DiffChange {
+                path: "type".into(),
+                op: DiffOp::Added,
+                left: None,
+                right: Some(json!("event_notification")),
+            },
+            DiffChange {
+                path: "entityEventId".into(),
+                op: DiffOp::Changed,
+                left: Some(json!(1)),
+                right: Some(json!(567)),
+            },
+            DiffChange {
+                path: "fields".into(),
+                op: DiffOp::Added,
+                left: None,
+                right: Some(json!([{"name": "vacancyId", "value": "42"}])),
+            },
+        ]
+    );
+
+    let item = service.get_item(ids[0]).await?.unwrap();
+    assert_eq!(item.summary.system.as_deref(), Some("stale"));
+
+    let result = service
+        .reclassify_items(&filter, &ReclassifyParams::default())
+        .await?;
+
+    assert!(!result.dry_run);
+    assert_eq!(result.changed, 3);
+
+    let item = service.get_item(ids[1]).await?.unwrap();
+    assert_eq!(item.summary.system.as_deref(), Some("system"));
+    assert_eq!(item.summary.entity_event_id, None);
+
+    let item = service.get_item(ids[0]).await?.unwrap();
+    assert_eq!(item.fields.len(), 1);
+
+    let result = service
+        .reclassify_items(&filter, &ReclassifyParams::default())
+        .await?;
+
+    assert_eq!(result.processed, 3);
+    assert_eq!(result.changed, 0);
+    assert!(result.items.is_empty());
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_reclassify_items_limits(repository: SqlitePool) -> Result<()> {
+    let service = new_service(repository.clone()).await?;
+
+    for _ in 0..105 {
+        service
+            .save_item(None, &[], b"<mgsSystem>system</mgsSystem>")
+            .await?;
+    }
+
+    sqlx::query("UPDATE item SET system = 'stale'")
+        .execute(&repository)
+        .await?;
+
+    let uri: Uri = "http://localhost?batchSize=0".parse()?;
+    let filter: Query<ItemFilter> = Query::try_from_uri(&uri)?;
+
+    let error = service
+        .reclassify_items(&filter, &ReclassifyParams::default())
+        .await
+        .err()
+        .unwrap();
+
+    assert!(error.is::<ValidationError>());
+
+    let result = service
+        .reclassify_items(
+            &ItemFilter::default(),
+            &ReclassifyParams {
+                dry_run: Some(true),
+            },
+        )
+        .await?;
+
+    assert_eq!(result.processed, 105);
+    assert_eq!(result.changed, 105);
+    assert_eq!(result.items.len(), 100);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_reclassify_items_sorted(repository: SqlitePool) -> Result<()> {
+    let service = new_service(repository.clone()).await?;
+
+    for _ in 0..3 {
+        service
+            .save_item(None, &[], b"<mgsSystem>system</mgsSystem>")
+            .await?;
+    }
+
+    sqlx::query("UPDATE item SET system = 'stale-' || id")
+        .execute(&repository)
+        .await?;
+
+    let uri: Uri = "http://localhost?sort=system&batchSize=1".parse()?;
+    let filter: Query<ItemFilter> = Query::try_from_uri(&uri)?;
+
+    let result = service
+        .reclassify_items(&filter, &ReclassifyParams::default())
+        .await?;
+
+    assert_eq!(result.processed, 3);
+    assert_eq!(result.changed, 3);
+
+    Ok(())
+}
+
 #[sqlx::test]
 async fn test_save_item(repository: SqlitePool) -> Result<()> {
     const BODY: &[u8] = br#"{"entityEventId": 567}"#;
     const EVENT_ID: i64 = 123;
     const SYSTEM: &str = "system";
     const USER_AGENT: &str = "user-agent";
+    const PATH: &str = "/sink/events";
 
-    let service = new_service(repository)?;
+    let service = new_service(repository).await?;
 
     let id = service
         .save_item(
+            Some(PATH),
             &[
                 NewItemHeader {
                     name: "mgs-event-id",
@@ -94,6 +707,7 @@ async fn test_save_item(repository: SqlitePool) -> Result<()> {
     assert_eq!(Some(EVENT_ID), summary.event_id);
     assert_eq!(Some(567), summary.entity_event_id);
     assert_eq!(Some(USER_AGENT), summary.user_agent.as_deref());
+    assert_eq!(Some(PATH), summary.path.as_deref());
 
     assert_eq!(
         vec!["mgs-event-id", "mgs-system-id", "user-agent"],
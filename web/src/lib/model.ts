@@ -1,5 +1,11 @@
+export interface EventTypeGroup {
+	name: string;
+	types: { name: string; id: number }[];
+}
+
 export interface Item extends ItemSummary {
 	headers: ItemHeader[];
+	validationErrors: ItemValidationError[];
 	body: string;
 }
 
@@ -22,7 +28,10 @@ export interface ItemSummary {
 	type?: string;
 	eventId?: number;
 	entityEventId?: number;
+	eventGroup?: string;
+	eventType?: string;
 	userAgent?: string;
+	valid?: boolean;
 }
 
 export interface ItemType {
@@ -30,3 +39,8 @@ export interface ItemType {
 	key: string;
 	color: string;
 }
+
+export interface ItemValidationError {
+	path: string;
+	message: string;
+}
@@ -1,17 +1,13 @@
 import { base } from '$app/paths';
 import { error } from '@sveltejs/kit';
-import EVENT_TYPES from '../../../event.types.json';
-import ITEM_TYPES from '../../../item.types.json';
-import type { Item, ItemSearchResult, ItemSummary, ItemType } from './model';
+import type { EventTypeGroup, Item, ItemSearchResult, ItemSummary, ItemType } from './model';
 
 // eslint-disable-next-line @typescript-eslint/no-explicit-any
 declare let initialData: any;
 
 export const BATCH_SIZE = 50;
 
-export { ITEM_TYPES };
-
-ITEM_TYPES.sort((x, y) => x.name.localeCompare(y.name));
+let itemTypes: ItemType[] = [];
 
 export function formatBody(item: Item): { body: string; language: string } {
 	for (const header of item.headers) {
@@ -67,15 +63,9 @@ function formatXml(xml: string): string {
 	return formatted;
 }
 
-export function getEntityEventType(id?: number): string {
-	if (id) {
-		for (const group of EVENT_TYPES) {
-			for (const type of group.types) {
-				if (type.id === id) {
-					return `${id} - ${group.name} / ${type.name}`;
-				}
-			}
-		}
+export function getEntityEventType(item: ItemSummary): string {
+	if (item.entityEventId && item.eventGroup) {
+		return `${item.entityEventId} - ${item.eventGroup} / ${item.eventType}`;
 	}
 
 	return '';
@@ -95,6 +85,10 @@ function getInitialDataOnce() {
 	return data;
 }
 
+export function getItemTypes(): ItemType[] {
+	return itemTypes;
+}
+
 export function getUserAgent(item: ItemSummary): string {
 	const userAgent = item.userAgent;
 
@@ -120,7 +114,7 @@ export function getUserAgent(item: ItemSummary): string {
 }
 
 export function itemTypeFromKey(key: string): ItemType {
-	for (const type of ITEM_TYPES) {
+	for (const type of itemTypes) {
 		if (type.key === key) {
 			return type;
 		}
@@ -129,6 +123,18 @@ export function itemTypeFromKey(key: string): ItemType {
 	return { name: key, key, color: 'red' };
 }
 
+export async function loadEventTypes(
+	fetch: (input: RequestInfo) => Promise<Response>
+): Promise<EventTypeGroup[]> {
+	const response = await fetch(`${base}/api/event-types`);
+
+	if (!response.ok) {
+		error(500, await response.text());
+	}
+
+	return await response.json();
+}
+
 export async function loadItem(
 	fetch: (input: RequestInfo) => Promise<Response>,
 	itemId: number
@@ -148,6 +154,24 @@ export async function loadItem(
 	return item;
 }
 
+export async function loadItemTypes(
+	fetch: (input: RequestInfo) => Promise<Response>
+): Promise<ItemType[]> {
+	const response = await fetch(`${base}/api/item-types`);
+
+	if (!response.ok) {
+		error(500, await response.text());
+	}
+
+	const types: ItemType[] = await response.json();
+
+	itemTypes = types
+		.map((type) => ({ ...type, color: type.color ?? 'gray' }))
+		.sort((x, y) => x.name.localeCompare(y.name));
+
+	return itemTypes;
+}
+
 export async function loadItems(
 	fetch: (input: RequestInfo) => Promise<Response>,
 	params: URLSearchParams,
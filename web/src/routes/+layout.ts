@@ -1 +1,8 @@
+import { loadItemTypes } from '$lib/shared';
+import type { LayoutLoad } from './$types';
+
 export const ssr = false;
+
+export const load = (async ({ fetch }) => {
+	await loadItemTypes(fetch);
+}) satisfies LayoutLoad;